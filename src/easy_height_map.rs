@@ -1,18 +0,0 @@
-[
-	[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-	[0, 0, 0, 0,-4,-4, 0, 0, 0, 0, 0, 0, 0, 0, 0,-4,-4, 0, 0, 0],
-	[0, 0, 0, 0,-4,-4, 0, 0, 0, 0, 0, 0, 0, 0, 0,-4,-4,-4,-4,-4],
-	[0, 0, 5, 5, 5, 5, 1, 1, 5, 5, 5, 1, 1, 5, 0,-4,-4,-4,-4,-4],
-	[0, 0, 5, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 5, 5, 5,-4,-4, 0, 0],
-	[1, 1, 5, 9, 0, 0, 0, 0, 0, 0, 0, 0, 9, 9, 9, 5,-4,-4, 0, 0],
-	[9, 9, 9, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9, 5, 5, 5, 1, 0],
-	[5, 5, 5, 9, 0, 0, 9, 9, 9, 9, 9, 0, 0, 0, 9, 9, 9, 9, 1, 0],
-	[0, 0, 5, 9, 9, 9, 9, 0, 0, 0, 9, 9, 9, 9, 9, 0, 0, 9, 5, 5],
-	[0, 0, 5, 5, 5, 5, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9, 9, 9],
-	[0,-4,-4, 0, 0, 5, 9, 9, 9, 0, 0, 0, 0, 0, 0, 0, 0, 9, 5, 5],
-	[0,-4,-4, 0, 0, 5, 5, 5, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 5, 0],
-	[0, 0, 0, 0, 0, 0, 0, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 0],
-	[0, 0, 0,-4,-4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-	[0, 0, 0,-4,-4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-	[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-]
@@ -3,7 +3,11 @@ use std::sync::Mutex;
 use bevy::prelude::*;
 
 use super::utils::VisualMarker;
-use crate::gameplay::utils;
+use crate::gameplay::{
+	level_data::LevelData,
+	towers::{Banking, RangedShooterAir, RangedShooterLand, Tower},
+	ui, utils,
+};
 
 type Colour = Color;
 
@@ -44,10 +48,8 @@ pub fn move_cursor_and_camera(
 			Without<VisualMarker>,
 		),
 	>,
+	level_data: Res<LevelData>,
 ) {
-	const CAMERA_LIMITS_MIN: Vec3 = Vec3::new(-45.0, 10.0, -30.0);
-	const CAMERA_LIMITS_MAX: Vec3 = Vec3::new(-10.0, 10.0, 32.0);
-
 	static CURSOR_REFERENCE: Mutex<Vec3> = Mutex::new(Vec3::ZERO);
 
 	let mut inner = move || {
@@ -62,7 +64,7 @@ pub fn move_cursor_and_camera(
 		let dist = ray.intersect_plane(Vec3::new(0.0, 1.0, 0.0), Vec3::Y)?;
 		let raw_ray = ray.get_point(dist);
 
-		let v = utils::with_height(raw_ray);
+		let v = utils::with_height(&level_data.height_map, raw_ray);
 
 		let hl_coord =
 			if (-20.0..20.0).contains(&v.z) && (-16.0..16.0).contains(&v.x) && v.y >= 0.11 {
@@ -86,13 +88,39 @@ pub fn move_cursor_and_camera(
 		if button.pressed(MouseButton::Left) {
 			let diff = *v_cur - cur.translation;
 			trans.translation = (trans.translation + diff)
-				.max(CAMERA_LIMITS_MIN)
-				.min(CAMERA_LIMITS_MAX);
+				.max(level_data.camera.min)
+				.min(level_data.camera.max);
 		}
 
-		win.cursor.visible = mouse.y < 64.0 && mouse.x < 64.0 * 8.0;
+		win.cursor.visible = ui::over_hud(mouse, win.height());
 
 		Some(())
 	};
 	let _ = inner();
 }
+
+pub fn show_range(
+	banking: Res<Banking>,
+	towers: Query<
+		(
+			&Transform,
+			Option<&RangedShooterLand>,
+			Option<&RangedShooterAir>,
+		),
+		With<Tower>,
+	>,
+	mut range_query: Query<(&mut Transform, &mut Visibility), (With<Range>, Without<Tower>)>,
+) {
+	let Ok((mut trans, mut visibility)) = range_query.get_single_mut() else {
+		return;
+	};
+	let Some((tower_trans, land, air)) = banking.selected.and_then(|t| towers.get(t).ok()) else {
+		*visibility = Visibility::Hidden;
+		return;
+	};
+
+	let range = land.map_or(0.0, |r| **r).max(air.map_or(0.0, |r| **r));
+	trans.translation = Vec3::new(tower_trans.translation.x, 1.0, tower_trans.translation.z);
+	trans.scale = Vec3::new(range, 1.0, range);
+	*visibility = Visibility::Visible;
+}
@@ -1,19 +1,196 @@
-use bevy::prelude::*;
-use once_cell::sync::{Lazy, OnceCell};
+use bevy::{
+	ecs::{
+		query::{Has, WorldQuery},
+		system::EntityCommands,
+	},
+	prelude::*,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 
-use crate::{easy, gameplay::path::Path};
+use crate::gameplay::{
+	level_data::LevelData,
+	levels::{Difficulty, GameState},
+	path::Path,
+	save::SavedTimer,
+	stats::{EnemyStats, Stats},
+	towers::Banking,
+};
 
 type Colour = Color;
 
 // ------------------------------ ENEMIES --------------------------------
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EnemyType {
 	Slow,
 	Normal,
 	Fast,
 	Air,
 	Split,
+	SplitChild,
+	Boss,
+}
+
+impl EnemyType {
+	pub const ALL: [EnemyType; 7] = [
+		EnemyType::Slow,
+		EnemyType::Normal,
+		EnemyType::Fast,
+		EnemyType::Air,
+		EnemyType::Split,
+		EnemyType::SplitChild,
+		EnemyType::Boss,
+	];
+
+	// Money paid out when an enemy of this type is killed
+	pub fn bounty(&self, difficulty: &Difficulty, stats: &Stats) -> i32 {
+		let base = stats.enemy(*self).bounty;
+		match difficulty {
+			Difficulty::Easy => base,
+			Difficulty::Normal => base * 4 / 5,
+			Difficulty::Hard => base * 3 / 5,
+		}
+	}
+
+	pub fn spawn<'a, 'b, 'c>(
+		&self,
+		commands: &'c mut Commands<'a, 'b>,
+		path_selection: PathSelection,
+		progress: f32,
+		health_scale: HealthScale,
+		level_data: &LevelData,
+		stats: &Stats,
+	) -> EntityCommands<'a, 'b, 'c> {
+		let enemy_stats = stats.enemy(*self);
+		let mut enemy = match self {
+			EnemyType::Slow => commands.spawn(slow(path_selection, enemy_stats)),
+			EnemyType::Normal => commands.spawn(normal(path_selection, enemy_stats)),
+			EnemyType::Fast => commands.spawn(fast(path_selection, enemy_stats)),
+			EnemyType::Air => commands.spawn(air(path_selection, enemy_stats)),
+			EnemyType::Split => commands.spawn(split(path_selection, enemy_stats)),
+			EnemyType::SplitChild => commands.spawn(split_child(
+				path_selection,
+				progress,
+				level_data,
+				enemy_stats,
+			)),
+			EnemyType::Boss => commands.spawn(boss(path_selection, enemy_stats)),
+		};
+		enemy.insert((
+			Progress(progress),
+			Health::new(health_scale.apply(enemy_stats.health)),
+			health_scale,
+		));
+		enemy
+	}
+}
+
+// An enemy that's still on the map when the game gets saved
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedEnemy {
+	enemy_type: EnemyType,
+	path: usize,
+	progress: f32,
+	health: i32,
+	max_health: i32,
+	health_scale: f32,
+	// Boss phases that already happened shouldn't happen again
+	boss_phase: Option<usize>,
+	boss_burst: Option<(f32, SavedTimer)>,
+	immune: Option<SavedTimer>,
+	slowed: Option<(f32, SavedTimer)>,
+	burning: Vec<(i32, SavedTimer)>,
+	burn_tick: SavedTimer,
+}
+
+impl SavedEnemy {
+	pub fn new(
+		(enemy_type, path_selection, progress, health, health_scale, burning, boss, immune, slowed): (
+			&EnemyType,
+			&PathSelection,
+			&Progress,
+			&Health,
+			&HealthScale,
+			&Burning,
+			Option<&Boss>,
+			Option<&Immune>,
+			Option<&Slowed>,
+		),
+	) -> Self {
+		SavedEnemy {
+			enemy_type: *enemy_type,
+			path: path_selection.0,
+			progress: progress.0,
+			health: health.current,
+			max_health: health.max,
+			health_scale: health_scale.0,
+			boss_phase: boss.map(|boss| boss.phase),
+			boss_burst: boss
+				.and_then(|boss| boss.burst.as_ref())
+				.map(|(factor, timer)| (*factor, SavedTimer::new(timer))),
+			immune: immune.map(|immune| SavedTimer::new(&immune.0)),
+			slowed: slowed.map(|slowed| (slowed.factor, SavedTimer::new(&slowed.timer))),
+			burning: burning
+				.stacks
+				.iter()
+				.map(|(damage, timer)| (*damage, SavedTimer::new(timer)))
+				.collect(),
+			burn_tick: SavedTimer::new(&burning.tick),
+		}
+	}
+
+	pub fn restore(&self, commands: &mut Commands, level_data: &LevelData, stats: &Stats) {
+		let mut enemy = self.enemy_type.spawn(
+			commands,
+			PathSelection(self.path),
+			self.progress,
+			HealthScale(self.health_scale),
+			level_data,
+			stats,
+		);
+		enemy.insert((
+			Health {
+				max: self.max_health,
+				current: self.health,
+			},
+			Burning {
+				stacks: self
+					.burning
+					.iter()
+					.map(|(damage, timer)| (*damage, timer.restore(TimerMode::Once)))
+					.collect(),
+				tick: self.burn_tick.restore(TimerMode::Repeating),
+			},
+		));
+		if let Some(phase) = self.boss_phase {
+			enemy.insert(Boss {
+				phase,
+				burst: self
+					.boss_burst
+					.as_ref()
+					.map(|(factor, timer)| (*factor, timer.restore(TimerMode::Once))),
+			});
+		}
+		if let Some(timer) = &self.immune {
+			enemy.insert(Immune(timer.restore(TimerMode::Once)));
+		}
+		if let Some((factor, timer)) = &self.slowed {
+			enemy.insert(Slowed {
+				factor: *factor,
+				timer: timer.restore(TimerMode::Once),
+			});
+		}
+	}
+}
+
+#[derive(Event, Debug)]
+pub struct EnemyLeaked(pub EnemyType);
+
+#[derive(Event, Debug)]
+pub struct EnemyKilled {
+	pub entity: Entity,
+	pub enemy_type: EnemyType,
 }
 
 #[derive(Component, Debug)]
@@ -31,9 +208,50 @@ pub struct Fast;
 #[derive(Component, Debug)]
 pub struct Air;
 
+// How far above the path flying enemies travel
+pub const FLYING_HEIGHT: f32 = 2.0;
+
 #[derive(Component, Debug)]
 pub struct SplitParent;
 
+#[derive(Component, Debug, Default)]
+pub struct Boss {
+	// Index of the next phase in BOSS_PHASES
+	pub phase: usize,
+	// Speed multiplier and how long is left of it
+	pub burst: Option<(f32, Timer)>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum BossPhase {
+	SpeedBurst,
+	Minions,
+	Immunity,
+}
+
+// Each phase kicks in once health drops to the given fraction
+const BOSS_PHASES: [(f32, BossPhase); 3] = [
+	(0.75, BossPhase::SpeedBurst),
+	(0.5, BossPhase::Minions),
+	(0.25, BossPhase::Immunity),
+];
+const BOSS_BURST: f32 = 2.5;
+const BOSS_BURST_DURATION: f32 = 3.0;
+const BOSS_MINIONS: usize = 4;
+const BOSS_IMMUNITY_DURATION: f32 = 4.0;
+
+// Takes no damage until the timer runs out
+#[derive(Component, Debug)]
+pub struct Immune(pub Timer);
+
+// Scales the model on top of the walk cycle
+#[derive(Component, Debug)]
+pub struct Size(pub f32);
+
+// How many children a split enemy breaks into and how far apart they end up
+const SPLIT_CHILDREN: usize = 3;
+const SPLIT_SPACING: f32 = 0.004;
+
 #[derive(Component, Debug)]
 pub struct SplitChild;
 
@@ -53,43 +271,415 @@ impl Health {
 	}
 }
 
+// Multiplier on the stat health, from the spawn group. Split children and
+// boss minions take it over from whatever spawned them
+#[derive(Component, Debug, Copy, Clone)]
+pub struct HealthScale(pub f32);
+
+impl HealthScale {
+	pub fn apply(&self, health: i32) -> i32 {
+		((health as f32 * self.0).round() as i32).max(1)
+	}
+}
+
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub enum DamageType {
+	Physical,
+	Fire,
+	Water,
+	Energy,
+}
+
+// Fraction of each type of damage an enemy shrugs off
+#[derive(Component, Debug, Copy, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+	pub physical: f32,
+	pub fire: f32,
+	pub water: f32,
+	pub energy: f32,
+}
+
+impl Resistances {
+	pub fn against(&self, damage_type: DamageType) -> f32 {
+		match damage_type {
+			DamageType::Physical => self.physical,
+			DamageType::Fire => self.fire,
+			DamageType::Water => self.water,
+			DamageType::Energy => self.energy,
+		}
+	}
+
+	// How much of a hit actually gets through
+	pub fn taken(&self, damage: i32, damage_type: DamageType) -> i32 {
+		let factor = 1.0 - self.against(damage_type).clamp(0.0, 1.0);
+		(damage as f32 * factor).round() as i32
+	}
+}
+
+// Damage over time, every hit from a fire tower adds another stack. Every enemy
+// carries one from the start so hits landing in the same frame all stack up
+#[derive(Component, Debug)]
+pub struct Burning {
+	pub stacks: Vec<(i32, Timer)>,
+	pub tick: Timer,
+}
+
+impl Burning {
+	const MAX_STACKS: usize = 5;
+	const TICK: f32 = 0.5;
+
+	pub fn new() -> Self {
+		Burning {
+			stacks: Vec::new(),
+			tick: Timer::from_seconds(Self::TICK, TimerMode::Repeating),
+		}
+	}
+
+	pub fn add_stack(&mut self, damage: i32, duration: f32) {
+		if self.stacks.len() >= Self::MAX_STACKS {
+			// Refresh the oldest stack rather than growing forever
+			self.stacks.remove(0);
+		}
+		self.stacks
+			.push((damage, Timer::from_seconds(duration, TimerMode::Once)));
+	}
+}
+
+// Temporarily slowed down to a fraction of the base speed until the timer runs out
+#[derive(Component, Debug)]
+pub struct Slowed {
+	pub factor: f32,
+	pub timer: Timer,
+}
+
+impl Slowed {
+	// Never go below this fraction of the original speed
+	const FLOOR: f32 = 0.3;
+	const TINT: [f32; 3] = [0.5, 0.7, 1.0];
+}
+
+// The material a mesh inside a slowed enemy had before it was tinted
+#[derive(Component, Debug)]
+pub struct Untinted(Handle<StandardMaterial>);
+
 #[derive(Component, Debug)]
 pub struct Progress(pub f32);
 
+// Current speed, worked out from the base speed and whatever slows or speeds it up
 #[derive(Component, Debug)]
 pub struct Speed(pub f32);
 
+// Speed from the stats, before any modifiers
+#[derive(Component, Debug)]
+pub struct BaseSpeed(pub f32);
+
 #[derive(Component, Debug)]
 pub struct PathSelection(pub usize);
 
 #[derive(Bundle, Debug)]
 pub struct EnemyBundle {
 	pub enemy: Enemy,
+	pub enemy_type: EnemyType,
 	pub health: Health,
+	pub resistances: Resistances,
+	pub burning: Burning,
+	pub base_speed: BaseSpeed,
 	pub speed: Speed,
 	pub progress: Progress,
 	pub path_selection: PathSelection,
 }
 
 pub fn move_enemies(
-	mut query: Query<(&mut Transform, &Speed, &mut Progress, &PathSelection), With<Enemy>>,
+	mut commands: Commands,
+	mut query: Query<
+		(
+			Entity,
+			&mut Transform,
+			&Speed,
+			&mut Progress,
+			&PathSelection,
+			&EnemyType,
+			Has<Air>,
+		),
+		With<Enemy>,
+	>,
+	mut leaks: EventWriter<EnemyLeaked>,
+	level_data: Res<LevelData>,
 	d_time: Res<Time>,
 ) {
 	const AVG_RANGE: f32 = 0.005;
 
-	for (mut loc, speed, mut prog, path_selection) in query.iter_mut() {
+	for (entity, mut loc, speed, mut prog, path_selection, enemy_type, is_air) in query.iter_mut() {
 		prog.0 += speed.0 * d_time.delta_seconds();
-		let path = &easy::PATHS[path_selection.0];
+		if prog.0 >= 1.0 {
+			commands.entity(entity).despawn_recursive();
+			leaks.send(EnemyLeaked(*enemy_type));
+			continue;
+		}
+
+		let path = &level_data.paths[path_selection.0];
+		let altitude = if is_air {
+			Vec3::new(0.0, FLYING_HEIGHT, 0.0)
+		} else {
+			Vec3::ZERO
+		};
 		// Rounds out the corner
-		loc.translation =
+		let rounded =
 			(path.interpolate(prog.0 + AVG_RANGE) + path.interpolate(prog.0 - AVG_RANGE)) / 2.0;
+		loc.translation = rounded + altitude;
 
-		let towards = path.interpolate(prog.0 + AVG_RANGE);
+		let towards = path.interpolate(prog.0 + AVG_RANGE) + altitude;
 		loc.look_at(towards, Vec3::Y);
 	}
 }
 
-pub fn animate_enemies(time: Res<Time>, mut enemies: Query<&mut Transform, With<Enemy>>) {
+// Everything needed to hurt an enemy, so that damage_enemy can be called from any query
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub struct Damageable {
+	pub entity: Entity,
+	pub health: &'static mut Health,
+	pub enemy_type: &'static EnemyType,
+	pub resistances: &'static Resistances,
+	pub immune: Option<&'static Immune>,
+}
+
+// Every source of damage goes through here so resistances are applied
+// consistently and kills are only reported once
+pub fn damage_enemy(
+	target: &mut DamageableItem,
+	damage: i32,
+	damage_type: DamageType,
+	kills: &mut EventWriter<EnemyKilled>,
+) {
+	// Only the hit that takes it below zero gets to kill it
+	if target.health.current <= 0 || target.immune.is_some() {
+		return;
+	}
+	target.health.current -= target.resistances.taken(damage, damage_type);
+
+	if target.health.current <= 0 {
+		kills.send(EnemyKilled {
+			entity: target.entity,
+			enemy_type: *target.enemy_type,
+		});
+	}
+}
+
+pub fn slow_enemy(
+	commands: &mut Commands,
+	entity: Entity,
+	slowed: Option<Mut<Slowed>>,
+	factor: f32,
+	duration: f32,
+) {
+	let factor = factor.max(Slowed::FLOOR);
+	let timer = Timer::from_seconds(duration, TimerMode::Once);
+	match slowed {
+		Some(mut slowed) => {
+			slowed.timer = timer;
+			slowed.factor = slowed.factor.min(factor);
+		}
+		None => {
+			commands.entity(entity).insert(Slowed { factor, timer });
+		}
+	}
+}
+
+pub fn thaw_enemies(
+	mut commands: Commands,
+	mut enemies: Query<(Entity, &mut Slowed), With<Enemy>>,
+	time: Res<Time>,
+) {
+	for (entity, mut slowed) in enemies.iter_mut() {
+		if slowed.timer.tick(time.delta()).finished() {
+			commands.entity(entity).remove::<Slowed>();
+		}
+	}
+}
+
+// Recomputed every frame rather than scaled in place, so slows and boss bursts
+// can start and end in any order without drifting away from the base speed
+pub fn update_speed(
+	mut enemies: Query<(&BaseSpeed, &mut Speed, Option<&Slowed>, Option<&Boss>), With<Enemy>>,
+) {
+	for (base, mut speed, slowed, boss) in enemies.iter_mut() {
+		let slow = slowed.map_or(1.0, |slowed| slowed.factor);
+		let burst = boss
+			.and_then(|boss| boss.burst.as_ref())
+			.map_or(1.0, |(factor, _)| *factor);
+		speed.0 = base.0 * slow * burst;
+	}
+}
+
+// Checks every slowed enemy rather than just newly slowed ones, their scene
+// (say, right after a save is restored) may not have spawned its meshes yet
+pub fn tint_slowed(
+	mut commands: Commands,
+	slowed: Query<Entity, With<Slowed>>,
+	mut thawed: RemovedComponents<Slowed>,
+	children: Query<&Children>,
+	mut meshes: Query<(&mut Handle<StandardMaterial>, Option<&Untinted>)>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	for entity in slowed.iter() {
+		for child in children.iter_descendants(entity) {
+			let Ok((mut handle, None)) = meshes.get_mut(child) else {
+				continue;
+			};
+			let Some(mut material) = materials.get(&handle).cloned() else {
+				continue;
+			};
+			// Materials are shared between all enemies of a type, so tint a copy
+			material.base_color *= Slowed::TINT;
+			let untinted = std::mem::replace(&mut *handle, materials.add(material));
+			commands.entity(child).insert(Untinted(untinted));
+		}
+	}
+
+	for entity in thawed.iter() {
+		for child in children.iter_descendants(entity) {
+			let Ok((mut handle, Some(untinted))) = meshes.get_mut(child) else {
+				continue;
+			};
+			*handle = untinted.0.clone();
+			commands.entity(child).remove::<Untinted>();
+		}
+	}
+}
+
+pub fn boss_phases(
+	mut commands: Commands,
+	mut bosses: Query<
+		(
+			Entity,
+			&mut Boss,
+			&Health,
+			&HealthScale,
+			&Progress,
+			&PathSelection,
+		),
+		With<Enemy>,
+	>,
+	level_data: Res<LevelData>,
+	stats: Res<Stats>,
+	time: Res<Time>,
+) {
+	for (entity, mut boss, health, health_scale, prog, path_selection) in bosses.iter_mut() {
+		let ratio = health.current as f32 / health.max as f32;
+		while let Some(&(threshold, phase)) = BOSS_PHASES.get(boss.phase) {
+			if ratio > threshold {
+				break;
+			}
+			boss.phase += 1;
+
+			match phase {
+				BossPhase::SpeedBurst => {
+					let timer = Timer::from_seconds(BOSS_BURST_DURATION, TimerMode::Once);
+					boss.burst = Some((BOSS_BURST, timer));
+				}
+				BossPhase::Minions => {
+					for i in 0..BOSS_MINIONS {
+						let progress = (prog.0 - SPLIT_SPACING * (i + 1) as f32).max(0.0);
+						EnemyType::Normal.spawn(
+							&mut commands,
+							PathSelection(path_selection.0),
+							progress,
+							*health_scale,
+							&level_data,
+							&stats,
+						);
+					}
+				}
+				BossPhase::Immunity => {
+					let timer = Timer::from_seconds(BOSS_IMMUNITY_DURATION, TimerMode::Once);
+					commands.entity(entity).insert(Immune(timer));
+				}
+			}
+		}
+
+		let Some((_, timer)) = &mut boss.burst else {
+			continue;
+		};
+		if timer.tick(time.delta()).finished() {
+			boss.burst = None;
+		}
+	}
+}
+
+pub fn expire_immunity(
+	mut commands: Commands,
+	mut enemies: Query<(Entity, &mut Immune), With<Enemy>>,
+	time: Res<Time>,
+) {
+	for (entity, mut immune) in enemies.iter_mut() {
+		if immune.0.tick(time.delta()).finished() {
+			commands.entity(entity).remove::<Immune>();
+		}
+	}
+}
+
+pub fn burn_enemies(
+	mut enemies: Query<(Damageable, &mut Burning), With<Enemy>>,
+	mut kills: EventWriter<EnemyKilled>,
+	time: Res<Time>,
+) {
+	for (mut target, mut burning) in enemies.iter_mut() {
+		if burning.stacks.is_empty() {
+			continue;
+		}
+		if burning.tick.tick(time.delta()).just_finished() {
+			let damage = burning.stacks.iter().map(|(damage, _)| damage).sum();
+			damage_enemy(&mut target, damage, DamageType::Fire, &mut kills);
+		}
+
+		burning
+			.stacks
+			.retain_mut(|(_, timer)| !timer.tick(time.delta()).finished());
+		// The next fire hit starts a fresh tick
+		if burning.stacks.is_empty() {
+			burning.tick.reset();
+		}
+	}
+}
+
+pub fn kill_enemies(
+	mut kills: EventReader<EnemyKilled>,
+	mut commands: Commands,
+	mut banking: ResMut<Banking>,
+	level: Res<GameState>,
+	level_data: Res<LevelData>,
+	stats: Res<Stats>,
+	parents: Query<(&Progress, &PathSelection, &HealthScale), With<SplitParent>>,
+) {
+	for kill in kills.iter() {
+		commands.entity(kill.entity).despawn_recursive();
+		banking.balance += kill.enemy_type.bounty(&level.difficulty, &stats);
+
+		let Ok((prog, path_selection, health_scale)) = parents.get(kill.entity) else {
+			continue;
+		};
+		// Spread the children out around where the parent died
+		let first = prog.0 - SPLIT_SPACING * (SPLIT_CHILDREN - 1) as f32 / 2.0;
+		for i in 0..SPLIT_CHILDREN {
+			let progress = (first + SPLIT_SPACING * i as f32).max(0.0);
+			EnemyType::SplitChild.spawn(
+				&mut commands,
+				PathSelection(path_selection.0),
+				progress,
+				*health_scale,
+				&level_data,
+				&stats,
+			);
+		}
+	}
+}
+
+pub fn animate_enemies(
+	time: Res<Time>,
+	mut enemies: Query<(&mut Transform, Option<&Size>), With<Enemy>>,
+) {
 	static WALK_CYCLE: Lazy<Path> = Lazy::new(|| {
 		Path::new(&[
 			Vec3::new(100.0, 100.0, 100.0),
@@ -105,42 +695,30 @@ pub fn animate_enemies(time: Res<Time>, mut enemies: Query<&mut Transform, With<
 	const SIZE_SCALING_FACTOR: f32 = 0.005;
 
 	let secs = time.elapsed_seconds();
-	for mut trans in enemies.iter_mut() {
+	for (mut trans, size) in enemies.iter_mut() {
 		let dt = (secs * TIME_SCALING_FACTOR) % 1.0;
-		trans.scale = SIZE_SCALING_FACTOR * WALK_CYCLE.interpolate(dt);
+		let size = SIZE_SCALING_FACTOR * size.map_or(1.0, |s| s.0);
+		trans.scale = size * WALK_CYCLE.interpolate(dt);
 	}
 }
 
 // Static handles to prevent reloading the same asset over and over again.
 // Might already be handled by Bevy though
-static SLOW_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static NORMAL_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static FAST_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static AIR_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static SPLIT_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-
-pub fn init_enemies(asset_server: Res<AssetServer>) {
-	SLOW_SCENE.get_or_init(|| asset_server.load("exported/Slow.gltf#Scene0"));
-	NORMAL_SCENE.get_or_init(|| asset_server.load("exported/Normal.gltf#Scene0"));
-	FAST_SCENE.get_or_init(|| asset_server.load("exported/Fast.gltf#Scene0"));
-	AIR_SCENE.get_or_init(|| asset_server.load("exported/Air.gltf#Scene0"));
-	SPLIT_SCENE.get_or_init(|| asset_server.load("exported/Split.gltf#Scene0"));
-}
-
-pub fn slow(path_selection: PathSelection) -> impl Bundle {
+pub fn slow(path_selection: PathSelection, stats: &EnemyStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: SLOW_SCENE
-				.get()
-				.expect("Slow scene should've been initialised")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(0.0, 1.5, 0.0).with_scale(Vec3::ONE * 0.5),
 			..default()
 		},
 		EnemyBundle {
 			enemy: Enemy,
-			speed: Speed(0.01),
-			health: Health::new(10),
+			enemy_type: EnemyType::Slow,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
 			progress: Progress(0.0),
 			path_selection,
 		},
@@ -148,20 +726,21 @@ pub fn slow(path_selection: PathSelection) -> impl Bundle {
 	)
 }
 
-pub fn normal(path_selection: PathSelection) -> impl Bundle {
+pub fn normal(path_selection: PathSelection, stats: &EnemyStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: NORMAL_SCENE
-				.get()
-				.expect("Slow scene should've been initialised")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(0.0, 1.5, 0.0).with_scale(Vec3::ONE * 0.5),
 			..default()
 		},
 		EnemyBundle {
 			enemy: Enemy,
-			speed: Speed(0.02),
-			health: Health::new(10),
+			enemy_type: EnemyType::Normal,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
 			progress: Progress(0.0),
 			path_selection,
 		},
@@ -169,20 +748,21 @@ pub fn normal(path_selection: PathSelection) -> impl Bundle {
 	)
 }
 
-pub fn fast(path_selection: PathSelection) -> impl Bundle {
+pub fn fast(path_selection: PathSelection, stats: &EnemyStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: FAST_SCENE
-				.get()
-				.expect("Slow scene should've been initialised")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(0.0, 1.5, 0.0).with_scale(Vec3::ONE * 0.5),
 			..default()
 		},
 		EnemyBundle {
 			enemy: Enemy,
-			speed: Speed(0.04),
-			health: Health::new(100),
+			enemy_type: EnemyType::Fast,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
 			progress: Progress(0.0),
 			path_selection,
 		},
@@ -190,44 +770,101 @@ pub fn fast(path_selection: PathSelection) -> impl Bundle {
 	)
 }
 
-pub fn air(path_selection: PathSelection) -> impl Bundle {
+pub fn air(path_selection: PathSelection, stats: &EnemyStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: AIR_SCENE
-				.get()
-				.expect("Slow scene should've been initialised")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(0.0, 1.5, 0.0).with_scale(Vec3::ONE * 0.5),
 			..default()
 		},
 		EnemyBundle {
 			enemy: Enemy,
-			speed: Speed(0.04),
-			health: Health::new(1000),
+			enemy_type: EnemyType::Air,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
 			progress: Progress(0.0),
 			path_selection,
 		},
-		Fast,
+		Air,
 	)
 }
 
-pub fn split(path_selection: PathSelection) -> impl Bundle {
+pub fn split(path_selection: PathSelection, stats: &EnemyStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: SPLIT_SCENE
-				.get()
-				.expect("Slow scene should've been initialised")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(0.0, 1.5, 0.0).with_scale(Vec3::ONE * 0.5),
 			..default()
 		},
 		EnemyBundle {
 			enemy: Enemy,
-			speed: Speed(0.04),
-			health: Health::new(1000),
+			enemy_type: EnemyType::Split,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
 			progress: Progress(0.0),
 			path_selection,
 		},
-		Fast,
+		SplitParent,
+	)
+}
+
+pub fn split_child(
+	path_selection: PathSelection,
+	progress: f32,
+	level_data: &LevelData,
+	stats: &EnemyStats,
+) -> impl Bundle {
+	(
+		SceneBundle {
+			scene: stats.scene.clone(),
+			// Start where the parent died rather than popping in at the origin
+			transform: Transform::from_translation(
+				level_data.paths[path_selection.0].interpolate(progress),
+			)
+			.with_scale(Vec3::ONE * 0.3),
+			..default()
+		},
+		EnemyBundle {
+			enemy: Enemy,
+			enemy_type: EnemyType::SplitChild,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
+			progress: Progress(progress),
+			path_selection,
+		},
+		SplitChild,
+		Size(0.6),
+	)
+}
+
+pub fn boss(path_selection: PathSelection, stats: &EnemyStats) -> impl Bundle {
+	(
+		SceneBundle {
+			scene: stats.scene.clone(),
+			transform: Transform::from_xyz(0.0, 1.5, 0.0),
+			..default()
+		},
+		EnemyBundle {
+			enemy: Enemy,
+			enemy_type: EnemyType::Boss,
+			base_speed: BaseSpeed(stats.speed),
+			speed: Speed(stats.speed),
+			health: Health::new(stats.health),
+			resistances: stats.resistances,
+			burning: Burning::new(),
+			progress: Progress(0.0),
+			path_selection,
+		},
+		Boss::default(),
+		Size(2.0),
 	)
 }
@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+use once_cell::sync::OnceCell;
+
+use crate::gameplay::enemies::{Enemy, Health, Size};
+
+type Colour = Color;
+
+// ---------------------------- HEALTH BARS ------------------------------
+
+const WIDTH: f32 = 1.0;
+const HEIGHT: f32 = 0.12;
+// Distance above the enemy's origin, before its Size is applied
+const OFFSET: f32 = 1.5;
+
+// Billboarded root of a health bar. Not a child of its enemy so the model's
+// scale can't squash or shear it, it follows the enemy around instead
+#[derive(Component, Debug)]
+pub struct HealthBar {
+	pub enemy: Entity,
+}
+
+#[derive(Component, Debug)]
+pub struct HealthBarBackground;
+
+#[derive(Component, Debug)]
+pub struct HealthBarFill;
+
+static HEALTH_BAR_MODEL: OnceCell<(
+	Handle<Mesh>,
+	Handle<StandardMaterial>,
+	Handle<StandardMaterial>,
+)> = OnceCell::new();
+
+pub fn init_health_bar_model(
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	let material = |colour: Colour| StandardMaterial {
+		base_color: colour,
+		unlit: true,
+		..default()
+	};
+	HEALTH_BAR_MODEL.get_or_init(|| {
+		(
+			meshes.add(shape::Quad::new(Vec2::new(WIDTH, HEIGHT)).into()),
+			materials.add(material(Colour::rgb(0.1, 0.1, 0.1))),
+			materials.add(material(Colour::rgb(0.1, 0.9, 0.1))),
+		)
+	});
+}
+
+pub fn attach_health_bars(mut commands: Commands, enemies: Query<Entity, Added<Enemy>>) {
+	let (mesh, background, fill) = HEALTH_BAR_MODEL
+		.get()
+		.expect("Health bar model should've been initialised");
+
+	for enemy in enemies.iter() {
+		commands
+			.spawn((
+				SpatialBundle {
+					visibility: Visibility::Hidden,
+					..default()
+				},
+				HealthBar { enemy },
+			))
+			.with_children(|bar| {
+				bar.spawn((
+					PbrBundle {
+						mesh: mesh.clone(),
+						material: background.clone(),
+						..default()
+					},
+					HealthBarBackground,
+				));
+				// Slightly in front so it doesn't z-fight with the background
+				bar.spawn((
+					PbrBundle {
+						mesh: mesh.clone(),
+						material: fill.clone(),
+						transform: Transform::from_xyz(0.0, 0.0, 0.001),
+						..default()
+					},
+					HealthBarFill,
+				));
+			});
+	}
+}
+
+// Bars aren't children of their enemy, so they don't go away along with it
+pub fn remove_health_bars(
+	mut commands: Commands,
+	bars: Query<(Entity, &HealthBar)>,
+	enemies: Query<(), With<Enemy>>,
+) {
+	for (entity, bar) in bars.iter() {
+		if !enemies.contains(bar.enemy) {
+			commands.entity(entity).despawn_recursive();
+		}
+	}
+}
+
+// New bars are filled in too, their enemy may have been hurt or restored from
+// a save before the bar was attached
+pub fn update_health_bars(
+	enemies: Query<Ref<Health>, With<Enemy>>,
+	mut bars: Query<(Ref<HealthBar>, &mut Visibility, &Children)>,
+	mut fills: Query<&mut Transform, With<HealthBarFill>>,
+) {
+	for (bar, mut visibility, bar_children) in bars.iter_mut() {
+		let Ok(health) = enemies.get(bar.enemy) else {
+			continue;
+		};
+		if !health.is_changed() && !bar.is_added() {
+			continue;
+		}
+
+		if health.current >= health.max {
+			*visibility = Visibility::Hidden;
+			continue;
+		}
+		*visibility = Visibility::Inherited;
+
+		let ratio = health.current.max(0) as f32 / health.max as f32;
+		for &child in bar_children.iter() {
+			let Ok(mut fill) = fills.get_mut(child) else {
+				continue;
+			};
+			// Shrink towards the left edge
+			fill.scale.x = ratio;
+			fill.translation.x = -(1.0 - ratio) * WIDTH / 2.0;
+		}
+	}
+}
+
+pub fn orient_health_bars(
+	camera: Query<&GlobalTransform, With<Camera3d>>,
+	enemies: Query<(&Transform, Option<&Size>), (With<Enemy>, Without<HealthBar>)>,
+	mut bars: Query<(&HealthBar, &mut Transform)>,
+) {
+	let Ok(camera) = camera.get_single() else {
+		return;
+	};
+	let (_, camera_rotation, _) = camera.to_scale_rotation_translation();
+
+	for (bar, mut trans) in bars.iter_mut() {
+		let Ok((enemy, size)) = enemies.get(bar.enemy) else {
+			continue;
+		};
+		trans.rotation = camera_rotation;
+		trans.translation = enemy.translation + Vec3::Y * OFFSET * size.map_or(1.0, |s| s.0);
+	}
+}
@@ -0,0 +1,297 @@
+use bevy::{
+	asset::{AssetLoader, LoadContext, LoadedAsset},
+	ecs::system::SystemParam,
+	prelude::*,
+	reflect::{TypePath, TypeUuid},
+	utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::gameplay::{
+	levels::{Difficulty, HeightMap, PathChoice, Wave},
+	path::Path,
+	stats::Stats,
+};
+
+// ----------------------------- LEVEL DATA ------------------------------
+
+// Textures of the ground plane, exported from blender
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroundTextures {
+	pub albedo: String,
+	pub overlay: String,
+	pub normal: String,
+	pub depth: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CameraSettings {
+	pub start: Vec3,
+	// Bounds for dragging the camera around
+	pub min: Vec3,
+	pub max: Vec3,
+}
+
+// What a .level.ron file looks like, turned into LevelData by the loader
+#[derive(Debug, Deserialize)]
+struct LevelFile {
+	// Shown on the menu, saves also find their level by it
+	name: String,
+	difficulty: Difficulty,
+	// 16 rows along x by 20 columns along z, one cell per 2x2 tiles. Nested
+	// lists, RON would want arrays written as tuples
+	height_map: Vec<Vec<i8>>,
+	// Keyframes for each path, consecutive ones differing along a single axis
+	paths: Vec<Vec<(i32, i32, i32)>>,
+	// Levels without art yet leave this out and get the height map drawn instead
+	#[serde(default)]
+	ground: Option<GroundTextures>,
+	camera: CameraSettings,
+	// The groups in a wave spawn alongside each other, each one enemy type every
+	// interval seconds after its delay. A group's path is Cycle, Fixed(n) or
+	// Weighted([..]) with a weight per path, and its health multiplies the stats
+	waves: Vec<Wave>,
+}
+
+// A level as loaded from assets/levels, also the resource for the level being played
+#[derive(Resource, Debug, Clone, TypeUuid, TypePath)]
+#[uuid = "3c1f0a56-2f1e-4d0b-9a43-7d2f5e8b6c11"]
+pub struct LevelData {
+	pub name: String,
+	pub difficulty: Difficulty,
+	pub height_map: HeightMap,
+	pub paths: Vec<Path>,
+	pub ground: Option<GroundTextures>,
+	pub camera: CameraSettings,
+	pub waves: Vec<Wave>,
+}
+
+impl TryFrom<LevelFile> for LevelData {
+	type Error = bevy::asset::Error;
+
+	fn try_from(file: LevelFile) -> Result<Self, Self::Error> {
+		let mut height_map: HeightMap = Default::default();
+		if file.height_map.len() != height_map.len()
+			|| file
+				.height_map
+				.iter()
+				.any(|row| row.len() != height_map[0].len())
+		{
+			return Err(bevy::asset::Error::msg(format!(
+				"height map should be {} rows of {}",
+				height_map.len(),
+				height_map[0].len()
+			)));
+		}
+		for (row, file_row) in height_map.iter_mut().zip(&file.height_map) {
+			row.copy_from_slice(file_row);
+		}
+
+		if file.paths.is_empty() {
+			return Err(bevy::asset::Error::msg("level has no paths"));
+		}
+		if file.waves.is_empty() {
+			return Err(bevy::asset::Error::msg("level has no waves"));
+		}
+		// Paths are interpolated one axis at a time
+		for (i, keyframes) in file.paths.iter().enumerate() {
+			let diagonal = keyframes.windows(2).any(|pair| {
+				let ((x1, y1, z1), (x2, y2, z2)) = (pair[0], pair[1]);
+				[x1 != x2, y1 != y2, z1 != z2]
+					.iter()
+					.filter(|&&d| d)
+					.count() > 1
+			});
+			if keyframes.len() < 2 || diagonal {
+				return Err(bevy::asset::Error::msg(format!(
+					"path {i} needs at least two keyframes, each differing from the last along one axis"
+				)));
+			}
+		}
+
+		let path_count = file.paths.len();
+		for (i, group) in file.waves.iter().flat_map(|wave| &wave.groups).enumerate() {
+			let path_ok = match &group.path {
+				PathChoice::Cycle => true,
+				PathChoice::Fixed(path) => *path < path_count,
+				PathChoice::Weighted(weights) => {
+					weights.len() == path_count && weights.iter().sum::<u32>() > 0
+				}
+			};
+			if !path_ok {
+				return Err(bevy::asset::Error::msg(format!(
+					"spawn group {i} picks from paths the level doesn't have"
+				)));
+			}
+			if group.interval < 0.0 || group.delay < 0.0 || group.health <= 0.0 {
+				return Err(bevy::asset::Error::msg(format!(
+					"spawn group {i} needs a positive health multiplier and no negative timings"
+				)));
+			}
+		}
+
+		Ok(LevelData {
+			name: file.name,
+			difficulty: file.difficulty,
+			height_map,
+			paths: file
+				.paths
+				.iter()
+				.map(|keyframes| Path::from_keyframes(keyframes))
+				.collect(),
+			ground: file.ground,
+			camera: file.camera,
+			waves: file.waves,
+		})
+	}
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+	fn load<'a>(
+		&'a self,
+		bytes: &'a [u8],
+		load_context: &'a mut LoadContext,
+	) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+		Box::pin(async move {
+			let file: LevelFile = ron::de::from_bytes(bytes)?;
+			load_context.set_default_asset(LoadedAsset::new(LevelData::try_from(file)?));
+			Ok(())
+		})
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&["level.ron"]
+	}
+}
+
+// Every level file in assets/levels, in no particular order
+#[derive(Resource, Debug)]
+pub struct LevelHandles(Vec<Handle<LevelData>>);
+
+impl LevelHandles {
+	// The levels that have finished loading, easiest first, along with the
+	// index the menu refers to them by
+	pub fn loaded<'a>(&self, levels: &'a Assets<LevelData>) -> Vec<(usize, &'a LevelData)> {
+		let mut loaded: Vec<_> = self
+			.0
+			.iter()
+			.enumerate()
+			.filter_map(|(index, handle)| Some((index, levels.get(handle)?)))
+			.collect();
+		loaded.sort_by(|(_, a), (_, b)| (a.difficulty, &a.name).cmp(&(b.difficulty, &b.name)));
+		loaded
+	}
+}
+
+pub fn load_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+	// Only files with a registered loader come back, so these are all levels
+	let handles = match asset_server.load_folder("levels") {
+		Ok(handles) => handles.into_iter().map(|handle| handle.typed()).collect(),
+		Err(err) => {
+			error!("Couldn't find the levels: {err}");
+			Vec::new()
+		}
+	};
+	commands.insert_resource(LevelHandles(handles));
+}
+
+// Everything that has to be loaded before a game can start
+#[derive(SystemParam)]
+pub struct GameAssets<'w> {
+	level_handles: Res<'w, LevelHandles>,
+	levels: Res<'w, Assets<LevelData>>,
+	stats: Option<Res<'w, Stats>>,
+}
+
+impl GameAssets<'_> {
+	// The level behind a menu button, or what is still loading
+	pub fn level(&self, index: usize) -> Result<&LevelData, String> {
+		let level_data = self
+			.level_handles
+			.0
+			.get(index)
+			.and_then(|handle| self.levels.get(handle))
+			.ok_or("that level isn't loaded")?;
+		self.with_stats(level_data)
+	}
+
+	pub fn level_named(&self, name: &str) -> Result<&LevelData, String> {
+		let level_data = self
+			.level_handles
+			.loaded(&self.levels)
+			.into_iter()
+			.map(|(_, level_data)| level_data)
+			.find(|level_data| level_data.name == name)
+			.ok_or_else(|| format!("there's no level called {name}"))?;
+		self.with_stats(level_data)
+	}
+
+	fn with_stats<'a>(&self, level_data: &'a LevelData) -> Result<&'a LevelData, String> {
+		if self.stats.is_none() {
+			return Err("the tower and enemy stats aren't loaded".into());
+		}
+		Ok(level_data)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PATH: &str = "[[(0, 0, 0), (4, 0, 0), (4, 0, 6)]]";
+	const WAVES: &str = "[(groups: [(enemy: Normal, count: 3)])]";
+
+	fn height_map(rows: usize, columns: usize) -> String {
+		let row = format!("[{}]", vec!["0"; columns].join(", "));
+		format!("[{}]", vec![row; rows].join(", "))
+	}
+
+	fn load(height_map: &str, paths: &str, waves: &str) -> Result<LevelData, bevy::asset::Error> {
+		let text = format!(
+			"(
+				name: \"Test\",
+				difficulty: Easy,
+				height_map: {height_map},
+				paths: {paths},
+				camera: (start: (0.0, 0.0, 0.0), min: (0.0, 0.0, 0.0), max: (0.0, 0.0, 0.0)),
+				waves: {waves},
+			)"
+		);
+		let file: LevelFile = ron::from_str(&text).expect("test level should parse");
+		LevelData::try_from(file)
+	}
+
+	#[test]
+	fn accepts_valid_level() {
+		let level = load(&height_map(16, 20), PATH, WAVES).unwrap();
+		assert_eq!(level.paths.len(), 1);
+		assert!(level.ground.is_none());
+	}
+
+	#[test]
+	fn rejects_wrong_height_map_size() {
+		assert!(load(&height_map(15, 20), PATH, WAVES).is_err());
+		assert!(load(&height_map(16, 19), PATH, WAVES).is_err());
+	}
+
+	#[test]
+	fn rejects_diagonal_path() {
+		let paths = "[[(0, 0, 0), (4, 0, 6)]]";
+		assert!(load(&height_map(16, 20), paths, WAVES).is_err());
+	}
+
+	#[test]
+	fn rejects_fixed_path_out_of_range() {
+		let waves = "[(groups: [(enemy: Normal, count: 3, path: Fixed(1))])]";
+		assert!(load(&height_map(16, 20), PATH, waves).is_err());
+	}
+
+	#[test]
+	fn rejects_zero_weights() {
+		let waves = "[(groups: [(enemy: Normal, count: 3, path: Weighted([0]))])]";
+		assert!(load(&height_map(16, 20), PATH, waves).is_err());
+	}
+}
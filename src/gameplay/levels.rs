@@ -1,64 +1,371 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use super::enemies::{EnemyType, PathSelection};
-use crate::{easy::Wave, gameplay::enemies};
+use super::enemies::{Enemy, EnemyLeaked, EnemyType, HealthScale, PathSelection};
+use crate::gameplay::{level_data::LevelData, stats::Stats, utils};
 
 type Colour = Color;
 
 // ------------------------------ LEVELS ---------------------------------
 
-#[derive(Debug)]
+pub type HeightMap = [[i8; 20]; 16];
+pub const WATER: i8 = -4;
+
+#[derive(States, Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+	#[default]
+	MainMenu,
+	Playing,
+	Paused,
+	GameOver,
+	Victory,
+}
+
+// Everything that belongs to a level and goes away with it when returning to the menu
+#[derive(Component, Debug)]
+pub struct LevelEntity;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Difficulty {
 	Easy,
 	Normal,
 	Hard,
 }
 
-#[derive(Resource, Debug)]
-pub struct SpawnTimer(pub Timer);
+// Which path each enemy of a group walks down
+#[derive(Debug, Clone, Default, Deserialize)]
+pub enum PathChoice {
+	// Every path in turn
+	#[default]
+	Cycle,
+	Fixed(usize),
+	// Random, one weight per path
+	Weighted(Vec<u32>),
+}
+
+impl PathChoice {
+	fn pick(&self, spawned: u32, path_count: usize) -> usize {
+		match self {
+			PathChoice::Cycle => spawned as usize % path_count,
+			PathChoice::Fixed(path) => *path,
+			PathChoice::Weighted(weights) => {
+				let mut roll = fastrand::u32(0..weights.iter().sum::<u32>());
+				weights
+					.iter()
+					.position(|&weight| {
+						let hit = roll < weight;
+						roll = roll.saturating_sub(weight);
+						hit
+					})
+					.unwrap_or(0)
+			}
+		}
+	}
+}
+
+// A stream of one enemy type, groups of a wave spawn alongside each other
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnGroup {
+	pub enemy: EnemyType,
+	pub count: u32,
+	// Seconds between two enemies of the group
+	#[serde(default = "SpawnGroup::default_interval")]
+	pub interval: f32,
+	// Seconds into the wave before the first one shows up
+	#[serde(default)]
+	pub delay: f32,
+	#[serde(default)]
+	pub path: PathChoice,
+	// Multiplies the health from the stats
+	#[serde(default = "SpawnGroup::default_health")]
+	pub health: f32,
+}
+
+impl SpawnGroup {
+	fn default_interval() -> f32 {
+		2.0
+	}
+
+	fn default_health() -> f32 {
+		1.0
+	}
+
+	// Seconds into the wave the nth enemy of the group spawns at
+	fn spawn_time(&self, n: u32) -> f32 {
+		self.delay + self.interval * n as f32
+	}
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Wave {
+	pub groups: Vec<SpawnGroup>,
+}
+
+// How far into its wave the level is, enough to pick spawning back up after a load
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WaveProgress {
+	pub elapsed: f32,
+	// Enemies spawned so far, per group
+	pub spawned: Vec<u32>,
+}
+
+impl WaveProgress {
+	pub fn new(wave: &Wave) -> Self {
+		WaveProgress {
+			elapsed: 0.0,
+			spawned: vec![0; wave.groups.len()],
+		}
+	}
+}
 
 #[derive(Resource, Debug)]
 pub struct GameState {
 	pub number: usize,
 	pub active: bool,
+	pub cleared: bool,
+	pub lives: i32,
 	pub difficulty: Difficulty,
-	pub wave: Wave,
+	pub wave: WaveProgress,
+}
+
+impl GameState {
+	pub fn new(difficulty: Difficulty, waves: &[Wave]) -> Self {
+		GameState {
+			number: 0,
+			active: true,
+			cleared: false,
+			lives: 20,
+			difficulty,
+			wave: waves.first().map(WaveProgress::new).unwrap_or_default(),
+		}
+	}
+
+	// Loads the next wave if the current one has been cleared, returning its number
+	pub fn next_wave(&mut self, waves: &[Wave]) -> Option<usize> {
+		if !self.cleared || self.lives <= 0 {
+			return None;
+		}
+		let wave = waves.get(self.number + 1)?;
+
+		self.number += 1;
+		self.wave = WaveProgress::new(wave);
+		self.active = true;
+		self.cleared = false;
+		Some(self.number)
+	}
 }
 
+#[derive(Event, Debug)]
+pub struct WaveStarted(pub usize);
+
+#[derive(Event, Debug)]
+pub struct WaveCleared(pub usize);
+
 #[derive(Event, Debug)]
 pub struct LevelEnd;
 
+#[derive(Event, Debug)]
+pub struct GameOver;
+
+pub fn setup_level(
+	mut commands: Commands,
+	asset_server: Res<AssetServer>,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+	level_data: Res<LevelData>,
+) {
+	utils::spawn_axes(&mut commands, &mut meshes, &mut materials);
+	utils::spawn_cursors(&mut commands, &mut meshes, &mut materials, &asset_server);
+
+	let half_scale = Vec3 {
+		x: 0.5,
+		y: 1.0,
+		z: 0.5,
+	};
+
+	// Level
+	let ground: Handle<Mesh> = asset_server.load("exported/SimpleGround.gltf#Mesh0/Primitive0");
+	if let Some(textures) = &level_data.ground {
+		let depth: Handle<Image> = asset_server.load(&textures.depth);
+		let albedo: Handle<Image> = asset_server.load(&textures.albedo);
+		let overlay: Handle<Image> = asset_server.load(&textures.overlay);
+		let normal: Handle<Image> = asset_server.load(&textures.normal);
+
+		commands.spawn((
+			PbrBundle {
+				mesh: ground.clone(),
+				material: materials.add(StandardMaterial {
+					base_color_texture: Some(overlay),
+					normal_map_texture: Some(normal.clone()),
+					alpha_mode: AlphaMode::Mask(0.5),
+					depth_map: Some(depth),
+					parallax_mapping_method: ParallaxMappingMethod::Relief { max_steps: 3 },
+					parallax_depth_scale: 0.1,
+					perceptual_roughness: 0.2,
+					max_parallax_layer_count: 16.0,
+					..default()
+				}),
+				transform: Transform::from_xyz(0.0, 0.01, 0.0).with_scale(half_scale),
+				..default()
+			},
+			LevelEntity,
+		));
+		commands.spawn((
+			PbrBundle {
+				mesh: ground,
+				material: materials.add(StandardMaterial {
+					base_color_texture: Some(albedo),
+					normal_map_texture: Some(normal),
+					..default()
+				}),
+				transform: Transform::from_xyz(0.0, 0.0, 0.0).with_scale(half_scale),
+				..default()
+			},
+			LevelEntity,
+		));
+	} else {
+		// No art for this level yet, show where the paths and obstacles are instead
+		commands.spawn((
+			PbrBundle {
+				mesh: ground,
+				material: materials.add(Colour::rgb(0.3, 0.5, 0.3).into()),
+				transform: Transform::from_scale(half_scale),
+				..default()
+			},
+			LevelEntity,
+		));
+		utils::visualise_height_map(
+			&level_data.height_map,
+			&mut commands,
+			&mut meshes,
+			&mut materials,
+		);
+	}
+
+	// Light
+	commands.spawn((
+		DirectionalLightBundle {
+			directional_light: DirectionalLight {
+				shadows_enabled: true,
+				illuminance: 25_000.0,
+				..default()
+			},
+			transform: Transform::from_xyz(4.0, 8.0, 14.0).looking_at(Vec3::ZERO, Vec3::Y),
+			..default()
+		},
+		LevelEntity,
+	));
+
+	// Camera
+	commands.spawn((
+		Camera3dBundle {
+			// transform: Transform::from_xyz(-15.0, 12.5, 1.0)
+			// transform: Transform::from_xyz(-30.0, 25.0, 2.0)
+			transform: Transform::from_translation(level_data.camera.start)
+				.looking_at(Vec3::ZERO, Vec3::Y),
+			..default()
+		},
+		LevelEntity,
+	));
+}
+
 pub fn spawn_enemy(
 	time: Res<Time>,
 	mut level: ResMut<GameState>,
-	mut timer: ResMut<SpawnTimer>,
 	mut commands: Commands,
+	level_data: Res<LevelData>,
+	stats: Res<Stats>,
 ) {
-	if !level.active || !timer.0.tick(time.delta()).just_finished() {
+	if !level.active {
 		return;
 	}
-
-	let Some((enemy_type, path)) = level.wave.0.pop() else {
+	let Some(wave) = level_data.waves.get(level.number) else {
 		level.active = false;
 		return;
 	};
-	let path_selection = PathSelection(path as usize);
 
-	match enemy_type {
-		EnemyType::Slow => {
-			commands.spawn(enemies::slow(path_selection));
-		}
-		EnemyType::Normal => {
-			commands.spawn(enemies::normal(path_selection));
+	let WaveProgress { elapsed, spawned } = &mut level.wave;
+	*elapsed += time.delta_seconds();
+	for (group, spawned) in wave.groups.iter().zip(spawned.iter_mut()) {
+		// Several at once if a frame took longer than the interval
+		while *spawned < group.count && *elapsed >= group.spawn_time(*spawned) {
+			let path_selection = PathSelection(group.path.pick(*spawned, level_data.paths.len()));
+			group.enemy.spawn(
+				&mut commands,
+				path_selection,
+				0.0,
+				HealthScale(group.health),
+				&level_data,
+				&stats,
+			);
+			*spawned += 1;
 		}
-		EnemyType::Fast => {
-			commands.spawn(enemies::fast(path_selection));
-		}
-		EnemyType::Air => {
-			commands.spawn(enemies::air(path_selection));
+	}
+
+	let done = wave
+		.groups
+		.iter()
+		.zip(&level.wave.spawned)
+		.all(|(group, &spawned)| spawned >= group.count);
+	if done {
+		level.active = false;
+	}
+}
+
+pub fn clear_wave(
+	mut level: ResMut<GameState>,
+	level_data: Res<LevelData>,
+	enemies: Query<(), With<Enemy>>,
+	mut wave_cleared: EventWriter<WaveCleared>,
+	mut level_end: EventWriter<LevelEnd>,
+) {
+	if level.active || level.cleared || level.lives <= 0 || !enemies.is_empty() {
+		return;
+	}
+
+	level.cleared = true;
+	wave_cleared.send(WaveCleared(level.number));
+	if level.number + 1 >= level_data.waves.len() {
+		level_end.send(LevelEnd);
+	}
+}
+
+pub fn lose_lives(
+	mut leaks: EventReader<EnemyLeaked>,
+	mut level: ResMut<GameState>,
+	stats: Res<Stats>,
+	mut game_over: EventWriter<GameOver>,
+) {
+	for EnemyLeaked(enemy_type) in leaks.iter() {
+		// Already lost, don't send the event twice
+		if level.lives <= 0 {
+			continue;
 		}
-		EnemyType::Split => {
-			commands.spawn(enemies::split(path_selection));
+
+		level.lives = (level.lives - stats.enemy(*enemy_type).leak_cost).max(0);
+		if level.lives == 0 {
+			level.active = false;
+			game_over.send(GameOver);
 		}
 	}
 }
+
+pub fn end_game(
+	mut game_over: EventReader<GameOver>,
+	mut level_end: EventReader<LevelEnd>,
+	mut commands: Commands,
+	enemies: Query<Entity, With<Enemy>>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if level_end.iter().next().is_some() {
+		next_state.set(AppState::Victory);
+	}
+	if game_over.iter().next().is_none() {
+		return;
+	}
+
+	for enemy in enemies.iter() {
+		commands.entity(enemy).despawn_recursive();
+	}
+	next_state.set(AppState::GameOver);
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use crate::gameplay::{
+	level_data::{GameAssets, LevelData, LevelHandles},
+	levels::{AppState, GameState, WaveStarted},
+	save::{LoadGame, SaveGame},
+	towers::Banking,
+	ui,
+	volcano::Volcano,
+};
+
+type Colour = Color;
+
+// ------------------------------- MENU ----------------------------------
+
+// Multipliers for the game clock, picked from the HUD or with the number keys
+pub const GAME_SPEEDS: [f32; 3] = [1.0, 2.0, 4.0];
+
+#[derive(Component, Debug)]
+pub struct MenuEntity;
+
+// Filled with a button per level as the level files load
+#[derive(Component, Debug)]
+pub struct LevelList;
+
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub enum MenuButton {
+	// Index into LevelHandles
+	Play(usize),
+	Resume,
+	Menu,
+	Save,
+	Load,
+}
+
+pub fn setup_menu(mut commands: Commands) {
+	// The level camera is gone while in the menu, the UI still needs something to render to
+	commands.spawn((Camera2dBundle::default(), MenuEntity));
+	commands
+		.spawn((
+			NodeBundle {
+				style: Style {
+					width: Val::Percent(100.0),
+					height: Val::Percent(100.0),
+					flex_direction: FlexDirection::Column,
+					align_items: AlignItems::Center,
+					justify_content: JustifyContent::Center,
+					row_gap: Val::Px(16.0),
+					..default()
+				},
+				..default()
+			},
+			MenuEntity,
+		))
+		.with_children(|parent| {
+			parent.spawn(TextBundle::from_section(
+				"Pick a level",
+				TextStyle {
+					font_size: 64.0,
+					color: Colour::WHITE,
+					..default()
+				},
+			));
+			parent.spawn((
+				NodeBundle {
+					style: Style {
+						flex_direction: FlexDirection::Column,
+						align_items: AlignItems::Center,
+						row_gap: Val::Px(16.0),
+						..default()
+					},
+					..default()
+				},
+				LevelList,
+			));
+			ui::spawn_text_button(parent, "Load", MenuButton::Load);
+		});
+}
+
+// Rebuilds the level buttons when the menu opens and whenever a level file
+// loads or gets edited
+pub fn list_levels(
+	mut commands: Commands,
+	mut events: EventReader<AssetEvent<LevelData>>,
+	list: Query<(Entity, Ref<LevelList>)>,
+	level_handles: Res<LevelHandles>,
+	levels: Res<Assets<LevelData>>,
+) {
+	let changed = events.iter().count() > 0;
+	let Ok((entity, list)) = list.get_single() else {
+		return;
+	};
+	if !changed && !list.is_added() {
+		return;
+	}
+
+	commands
+		.entity(entity)
+		.despawn_descendants()
+		.with_children(|parent| {
+			for (index, level_data) in level_handles.loaded(&levels) {
+				ui::spawn_text_button(parent, &level_data.name, MenuButton::Play(index));
+			}
+		});
+}
+
+// The level buttons, which start a fresh game
+pub fn start_level(
+	buttons: Query<(&Interaction, &MenuButton), (With<Button>, Changed<Interaction>)>,
+	mut commands: Commands,
+	assets: GameAssets,
+	mut time: ResMut<Time>,
+	mut next_state: ResMut<NextState<AppState>>,
+	mut wave_started: EventWriter<WaveStarted>,
+) {
+	for (_, button) in buttons.iter().filter(|(&i, _)| i == Interaction::Pressed) {
+		let MenuButton::Play(index) = button else {
+			continue;
+		};
+		let level_data = match assets.level(*index) {
+			Ok(level_data) => level_data,
+			Err(err) => {
+				warn!("Can't start the level yet: {err}");
+				continue;
+			}
+		};
+		// Nothing carries over from a previous level
+		commands.insert_resource(GameState::new(level_data.difficulty, &level_data.waves));
+		commands.insert_resource(level_data.clone());
+		commands.insert_resource(Banking::new());
+		commands.insert_resource(Volcano::new());
+		time.set_relative_speed(1.0);
+		next_state.set(AppState::Playing);
+		// The first wave is already under way, later ones start from the Next button
+		wave_started.send(WaveStarted(0));
+	}
+}
+
+pub fn run_menu(
+	buttons: Query<(&Interaction, &MenuButton), (With<Button>, Changed<Interaction>)>,
+	mut next_state: ResMut<NextState<AppState>>,
+	mut saves: EventWriter<SaveGame>,
+	mut loads: EventWriter<LoadGame>,
+) {
+	for (_, button) in buttons.iter().filter(|(&i, _)| i == Interaction::Pressed) {
+		match button {
+			MenuButton::Play(_) => {}
+			MenuButton::Resume => next_state.set(AppState::Playing),
+			MenuButton::Menu => next_state.set(AppState::MainMenu),
+			MenuButton::Save => saves.send(SaveGame),
+			MenuButton::Load => loads.send(LoadGame),
+		}
+	}
+}
+
+pub fn toggle_pause(
+	keys: Res<Input<KeyCode>>,
+	state: Res<State<AppState>>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if !keys.just_pressed(KeyCode::Escape) {
+		return;
+	}
+	match state.get() {
+		AppState::Playing => next_state.set(AppState::Paused),
+		AppState::Paused => next_state.set(AppState::Playing),
+		_ => {}
+	}
+}
+
+pub fn change_speed(keys: Res<Input<KeyCode>>, mut time: ResMut<Time>) {
+	let keys_to_speeds = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3]
+		.into_iter()
+		.zip(GAME_SPEEDS);
+	for (key, speed) in keys_to_speeds {
+		if keys.just_pressed(key) {
+			time.set_relative_speed(speed);
+		}
+	}
+}
+
+// Stops the game clock so timers and animations pick up where they left off
+pub fn pause_time(mut time: ResMut<Time>) {
+	time.pause();
+}
+
+pub fn resume_time(mut time: ResMut<Time>) {
+	time.unpause();
+}
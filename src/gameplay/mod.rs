@@ -1,7 +1,13 @@
 pub mod cursor;
 pub mod enemies;
+pub mod health_bars;
+pub mod level_data;
 pub mod levels;
+pub mod menu;
 pub mod path;
+pub mod save;
+pub mod stats;
 pub mod towers;
 pub mod ui;
 pub mod utils;
+pub mod volcano;
@@ -1,17 +1,16 @@
 use bevy::prelude::*;
 
-use crate::gameplay::{towers, utils};
+use crate::gameplay::{levels::HeightMap, utils};
 
 type Colour = Color;
 
 // ------------------------------- PATH ----------------------------------
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Path(pub Vec<Vec3>);
 
 impl Path {
-	pub fn from_keyframes<const N: usize>(points: [(i32, i32, i32); N]) -> Self {
-		let mut occupied_map = towers::OCCUPIED_MAP.lock().unwrap();
+	pub fn from_keyframes(points: &[(i32, i32, i32)]) -> Self {
 		let interpolated = points
 			.iter()
 			.zip(points.iter().skip(1))
@@ -42,15 +41,11 @@ impl Path {
 						(z2..=z1).rev().map(as_z).collect::<Vec<Vec3>>()
 					}
 				} else {
-					panic!("Comptime data misconstructed")
+					panic!("Keyframes must differ along a single axis")
 				}
 				.into_iter()
 				.skip(1)
 			})
-			.inspect(|&v| {
-				let (x, y) = utils::to_map_space(v);
-				occupied_map[x][y] = true;
-			})
 			.collect();
 
 		Path(interpolated)
@@ -60,6 +55,13 @@ impl Path {
 		Path(points.to_vec())
 	}
 
+	// Whether any point of the path lies on the given map cell
+	pub fn covers(&self, height_map: &HeightMap, cell: (usize, usize)) -> bool {
+		self.0
+			.iter()
+			.any(|&v| utils::to_map_space(height_map, v) == cell)
+	}
+
 	pub fn interpolate(&self, dt: f32) -> Vec3 {
 		let dt_ = dt.max(0.0).min(1.0);
 		match self.0.len() {
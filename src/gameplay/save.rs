@@ -0,0 +1,248 @@
+use std::{error::Error, fs, path::PathBuf, time::Duration};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::gameplay::{
+	enemies::{
+		Boss, Burning, EnemyType, Health, HealthScale, Immune, PathSelection, Progress, SavedEnemy,
+		Slowed,
+	},
+	level_data::{GameAssets, LevelData},
+	levels::{AppState, GameState, WaveProgress},
+	stats::Stats,
+	towers::{
+		AttackSpeed, Banking, Damage, Invested, RangedShooterAir, RangedShooterLand, SavedTower,
+		Targeting, Tower, Upgraded,
+	},
+	volcano::Volcano,
+};
+
+// -------------------------------- SAVES --------------------------------
+
+// Bump whenever SaveFile changes shape, older saves get refused instead of half loaded
+const SAVE_VERSION: u32 = 1;
+
+#[derive(Event, Debug)]
+pub struct SaveGame;
+
+#[derive(Event, Debug)]
+pub struct LoadGame;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SaveFile {
+	version: u32,
+	// Name of the level, its difficulty comes along with it
+	level: String,
+	wave_number: usize,
+	active: bool,
+	cleared: bool,
+	lives: i32,
+	// Where spawning of the current wave is at
+	wave: WaveProgress,
+	balance: i32,
+	volcano_elapsed: f32,
+	towers: Vec<SavedTower>,
+	enemies: Vec<SavedEnemy>,
+}
+
+// Only the version, read first so a save from another version fails clearly
+#[derive(Deserialize)]
+#[serde(rename = "SaveFile")]
+struct SaveVersion {
+	version: u32,
+}
+
+// Towers and enemies from a loaded save, spawned once the level is set up
+#[derive(Resource, Debug)]
+pub struct PendingRestore {
+	towers: Vec<SavedTower>,
+	enemies: Vec<SavedEnemy>,
+}
+
+// A timer part way through, for the status effects still running on enemies
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedTimer {
+	duration: f32,
+	elapsed: f32,
+}
+
+impl SavedTimer {
+	pub fn new(timer: &Timer) -> Self {
+		SavedTimer {
+			duration: timer.duration().as_secs_f32(),
+			elapsed: timer.elapsed_secs(),
+		}
+	}
+
+	pub fn restore(&self, mode: TimerMode) -> Timer {
+		let mut timer = Timer::from_seconds(self.duration, mode);
+		timer.tick(Duration::from_secs_f32(self.elapsed));
+		timer
+	}
+}
+
+fn save_path() -> Option<PathBuf> {
+	Some(
+		dirs::data_dir()?
+			.join(env!("CARGO_PKG_NAME"))
+			.join("save.ron"),
+	)
+}
+
+fn write_save(save: &SaveFile) -> Result<PathBuf, Box<dyn Error>> {
+	let path = save_path().ok_or("couldn't find a data directory")?;
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	fs::write(
+		&path,
+		ron::ser::to_string_pretty(save, PrettyConfig::default())?,
+	)?;
+	Ok(path)
+}
+
+fn read_save() -> Result<SaveFile, Box<dyn Error>> {
+	let path = save_path().ok_or("couldn't find a data directory")?;
+	let text = fs::read_to_string(path)?;
+
+	let SaveVersion { version } = ron::from_str(&text)?;
+	if version != SAVE_VERSION {
+		return Err(format!("save is version {version}, expected {SAVE_VERSION}").into());
+	}
+	Ok(ron::from_str(&text)?)
+}
+
+pub fn save_game(
+	mut saves: EventReader<SaveGame>,
+	level: Res<GameState>,
+	level_data: Option<Res<LevelData>>,
+	banking: Res<Banking>,
+	volcano: Res<Volcano>,
+	towers: Query<(
+		&Tower,
+		&Transform,
+		&Upgraded,
+		&Invested,
+		&Targeting,
+		&Damage,
+		&AttackSpeed,
+		Option<&RangedShooterLand>,
+		Option<&RangedShooterAir>,
+	)>,
+	enemies: Query<(
+		&EnemyType,
+		&PathSelection,
+		&Progress,
+		&Health,
+		&HealthScale,
+		&Burning,
+		Option<&Boss>,
+		Option<&Immune>,
+		Option<&Slowed>,
+	)>,
+) {
+	if saves.iter().next().is_none() {
+		return;
+	}
+	let Some(level_data) = level_data else {
+		error!("Couldn't save the game: no level is being played");
+		return;
+	};
+
+	let save = SaveFile {
+		version: SAVE_VERSION,
+		level: level_data.name.clone(),
+		wave_number: level.number,
+		active: level.active,
+		cleared: level.cleared,
+		lives: level.lives,
+		wave: level.wave.clone(),
+		balance: banking.balance,
+		volcano_elapsed: volcano.cooldown.elapsed_secs(),
+		towers: towers.iter().map(SavedTower::new).collect(),
+		// Dead enemies are only despawned once their kill is handled
+		enemies: enemies
+			.iter()
+			.filter(|(_, _, _, health, ..)| health.current > 0)
+			.map(SavedEnemy::new)
+			.collect(),
+	};
+	match write_save(&save) {
+		Ok(path) => info!("Saved the game to {}", path.display()),
+		Err(err) => error!("Couldn't save the game: {err}"),
+	}
+}
+
+pub fn load_game(
+	mut loads: EventReader<LoadGame>,
+	mut commands: Commands,
+	assets: GameAssets,
+	mut time: ResMut<Time>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	if loads.iter().next().is_none() {
+		return;
+	}
+	let save = match read_save() {
+		Ok(save) => save,
+		Err(err) => {
+			error!("Couldn't load the game: {err}");
+			return;
+		}
+	};
+	let level_data = match assets.level_named(&save.level) {
+		Ok(level_data) => level_data,
+		Err(err) => {
+			error!("Couldn't load the game: {err}");
+			return;
+		}
+	};
+
+	commands.insert_resource(level_data.clone());
+	commands.insert_resource(GameState {
+		number: save.wave_number,
+		active: save.active,
+		cleared: save.cleared,
+		lives: save.lives,
+		difficulty: level_data.difficulty,
+		wave: save.wave,
+	});
+	let mut banking = Banking::new();
+	banking.balance = save.balance;
+	commands.insert_resource(banking);
+	let mut volcano = Volcano::new();
+	// Ticked rather than set so the timer knows whether it has finished
+	volcano.cooldown.reset();
+	volcano
+		.cooldown
+		.tick(Duration::from_secs_f32(save.volcano_elapsed));
+	commands.insert_resource(volcano);
+	commands.insert_resource(PendingRestore {
+		towers: save.towers,
+		enemies: save.enemies,
+	});
+
+	time.set_relative_speed(1.0);
+	next_state.set(AppState::Playing);
+}
+
+pub fn restore_save(
+	mut commands: Commands,
+	pending: Option<Res<PendingRestore>>,
+	level_data: Res<LevelData>,
+	stats: Res<Stats>,
+) {
+	let Some(pending) = pending else {
+		return;
+	};
+
+	for tower in &pending.towers {
+		tower.restore(&mut commands, &level_data.height_map, &stats);
+	}
+	for enemy in &pending.enemies {
+		enemy.restore(&mut commands, &level_data, &stats);
+	}
+	commands.remove_resource::<PendingRestore>();
+}
@@ -0,0 +1,175 @@
+use bevy::{
+	asset::{AssetLoader, LoadContext, LoadedAsset},
+	prelude::*,
+	reflect::{TypePath, TypeUuid},
+	utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use crate::gameplay::{
+	enemies::{EnemyType, Resistances},
+	towers::Tower,
+};
+
+// -------------------------------- STATS --------------------------------
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TowerStats {
+	pub cost: i32,
+	pub damage: i32,
+	// Seconds between attacks
+	pub attack_speed: f32,
+	#[serde(default)]
+	pub land_range: Option<f32>,
+	#[serde(default)]
+	pub air_range: Option<f32>,
+	pub model: String,
+	// Loaded from the model path once the stats are in
+	#[serde(skip)]
+	pub scene: Handle<Scene>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyStats {
+	pub health: i32,
+	// Fraction of the path covered per second
+	pub speed: f32,
+	// Before the difficulty cut
+	pub bounty: i32,
+	// Lives lost when it reaches the end of its path
+	pub leak_cost: i32,
+	#[serde(default)]
+	pub resistances: Resistances,
+	pub model: String,
+	#[serde(skip)]
+	pub scene: Handle<Scene>,
+}
+
+// Balancing numbers from assets/game.stats.ron, also the resource new spawns read from
+#[derive(Resource, Debug, Clone, Deserialize, TypeUuid, TypePath)]
+#[uuid = "8e5b2d74-0c9a-4f3e-b1d6-52a7c4e9f013"]
+pub struct Stats {
+	towers: HashMap<Tower, TowerStats>,
+	enemies: HashMap<EnemyType, EnemyStats>,
+}
+
+impl Stats {
+	pub fn tower(&self, tower: Tower) -> &TowerStats {
+		&self.towers[&tower]
+	}
+
+	pub fn enemy(&self, enemy_type: EnemyType) -> &EnemyStats {
+		&self.enemies[&enemy_type]
+	}
+
+	fn validate(&self) -> Result<(), bevy::asset::Error> {
+		if let Some(tower) = Tower::ALL.iter().find(|t| !self.towers.contains_key(*t)) {
+			return Err(bevy::asset::Error::msg(format!(
+				"no stats for {tower:?} towers"
+			)));
+		}
+		if let Some(enemy) = EnemyType::ALL
+			.iter()
+			.find(|e| !self.enemies.contains_key(*e))
+		{
+			return Err(bevy::asset::Error::msg(format!(
+				"no stats for {enemy:?} enemies"
+			)));
+		}
+		for (tower, stats) in &self.towers {
+			// Turned into timers, which panic on zero or negative durations
+			if stats.attack_speed <= 0.0 {
+				return Err(bevy::asset::Error::msg(format!(
+					"{tower:?} towers need a positive attack speed"
+				)));
+			}
+			let negative_range = [stats.land_range, stats.air_range]
+				.iter()
+				.flatten()
+				.any(|&range| range < 0.0);
+			if stats.cost < 0 || negative_range {
+				return Err(bevy::asset::Error::msg(format!(
+					"{tower:?} towers can't have a negative cost or range"
+				)));
+			}
+		}
+		for (enemy, stats) in &self.enemies {
+			if stats.health <= 0 {
+				return Err(bevy::asset::Error::msg(format!(
+					"{enemy:?} enemies need some health"
+				)));
+			}
+			if stats.speed < 0.0 || stats.bounty < 0 {
+				return Err(bevy::asset::Error::msg(format!(
+					"{enemy:?} enemies can't have a negative speed or bounty"
+				)));
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(Default)]
+pub struct StatsLoader;
+
+impl AssetLoader for StatsLoader {
+	fn load<'a>(
+		&'a self,
+		bytes: &'a [u8],
+		load_context: &'a mut LoadContext,
+	) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+		Box::pin(async move {
+			let stats: Stats = ron::de::from_bytes(bytes)?;
+			stats.validate()?;
+			load_context.set_default_asset(LoadedAsset::new(stats));
+			Ok(())
+		})
+	}
+
+	fn extensions(&self) -> &[&str] {
+		&["stats.ron"]
+	}
+}
+
+#[derive(Resource, Debug)]
+pub struct StatsHandle(Handle<Stats>);
+
+pub fn load_stats(mut commands: Commands, asset_server: Res<AssetServer>) {
+	commands.insert_resource(StatsHandle(asset_server.load("game.stats.ron")));
+}
+
+// Copies the stats into the resource whenever the file is loaded or edited,
+// towers and enemies already on the map keep what they spawned with. An edit
+// that fails validation never makes it into the assets, so the last good stats
+// stay in use
+pub fn sync_stats(
+	mut events: EventReader<AssetEvent<Stats>>,
+	mut commands: Commands,
+	handle: Res<StatsHandle>,
+	assets: Res<Assets<Stats>>,
+	asset_server: Res<AssetServer>,
+) {
+	for event in events.iter() {
+		let (AssetEvent::Created { handle: changed } | AssetEvent::Modified { handle: changed }) =
+			event
+		else {
+			continue;
+		};
+		if *changed != handle.0 {
+			continue;
+		}
+		let Some(stats) = assets.get(&handle.0) else {
+			continue;
+		};
+
+		let mut stats = stats.clone();
+		for tower in stats.towers.values_mut() {
+			tower.scene = asset_server.load(&tower.model);
+		}
+		for enemy in stats.enemies.values_mut() {
+			enemy.scene = asset_server.load(&enemy.model);
+		}
+		info!("Loaded tower and enemy stats");
+		commands.insert_resource(stats);
+	}
+}
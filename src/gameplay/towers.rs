@@ -1,8 +1,4 @@
-use std::{
-	ops::Deref,
-	sync::Mutex,
-	time::{Duration, Instant},
-};
+use std::{ops::Deref, sync::Mutex, time::Duration};
 
 use bevy::{
 	ecs::{query::ReadOnlyWorldQuery, system::EntityCommands},
@@ -10,13 +6,19 @@ use bevy::{
 	prelude::*,
 };
 use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
 
-use crate::{
-	easy,
-	gameplay::{
-		enemies::{Air, Enemy, Health, PathSelection, Progress, Speed},
-		ui::{BalanceLabel, Click},
+use crate::gameplay::{
+	enemies::{
+		self, Air, Burning, DamageType, Damageable, DamageableReadOnlyItem, Enemy, EnemyKilled,
+		Health, PathSelection, Progress, Slowed, Speed,
 	},
+	level_data::LevelData,
+	levels::{self, HeightMap},
+	stats::{Stats, TowerStats},
+	ui::Click,
+	utils,
+	volcano::Volcano,
 };
 
 type Colour = Color;
@@ -24,9 +26,10 @@ type Colour = Color;
 // ------------------------------ TOWERS ---------------------------------
 
 const BULLET_TRAVEL_TIME: Duration = Duration::from_millis(500);
+const MAX_LEVEL: i32 = 3;
 pub static OCCUPIED_MAP: Mutex<[[bool; 20]; 16]> = Mutex::new([[false; 20]; 16]);
 
-#[derive(Component, Copy, Clone, PartialEq, Debug)]
+#[derive(Component, Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum Tower {
 	Land,
 	All,
@@ -37,31 +40,117 @@ pub enum Tower {
 }
 
 impl Tower {
-	fn cost(&self) -> i32 {
+	pub const ALL: [Tower; 6] = [
+		Tower::Land,
+		Tower::All,
+		Tower::Fire,
+		Tower::Water,
+		Tower::Air,
+		Tower::Laser,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Tower::Land => "Land",
+			Tower::All => "All",
+			Tower::Fire => "Fire",
+			Tower::Water => "Water",
+			Tower::Air => "Air",
+			Tower::Laser => "Laser",
+		}
+	}
+
+	fn bullet_colour(&self) -> Colour {
 		match self {
-			Tower::Land => 30,
-			Tower::All => 50,
-			Tower::Fire => 56,
-			Tower::Water => 50,
-			Tower::Air => 70,
-			Tower::Laser => 400,
+			Tower::Land => Colour::rgb(1.0, 1.0, 1.0),
+			Tower::All => Colour::rgb(1.0, 1.0, 1.0),
+			Tower::Fire => Colour::rgb(1.0, 0.4, 0.0),
+			Tower::Water => Colour::rgb(0.1, 0.4, 1.0),
+			Tower::Air => Colour::rgb(0.8, 1.0, 1.0),
+			Tower::Laser => Colour::rgb(1.0, 0.0, 0.2),
 		}
 	}
 
+	// Upgrades get more expensive the further up a tower is
+	fn upgrade_cost(&self, level: i32, stats: &Stats) -> Option<i32> {
+		(level < MAX_LEVEL).then(|| stats.tower(*self).cost * (level + 1))
+	}
+
 	fn spawn<'a, 'b, 'c>(
 		&self,
 		location: Vec3,
-		asset_server: &Res<AssetServer>,
 		commands: &'c mut Commands<'a, 'b>,
-		materials: &mut ResMut<Assets<StandardMaterial>>,
+		stats: &Stats,
 	) -> EntityCommands<'a, 'b, 'c> {
+		let tower_stats = stats.tower(*self);
+		let mut tower = match self {
+			Tower::Land => commands.spawn(land_tower(location, tower_stats)),
+			Tower::All => commands.spawn(all_tower(location, tower_stats)),
+			Tower::Fire => commands.spawn(fire_tower(location, tower_stats)),
+			Tower::Water => commands.spawn(water_tower(location, tower_stats)),
+			Tower::Air => commands.spawn(air_tower(location, tower_stats)),
+			Tower::Laser => {
+				let mut tower = commands.spawn(laser_tower(location, tower_stats));
+				tower.with_children(|parent| {
+					parent.spawn(beam_visual());
+				});
+				tower
+			}
+		};
+		// Which enemies a tower can hit at all comes from the stats as well
+		if let Some(range) = tower_stats.land_range {
+			tower.insert(RangedShooterLand(range));
+		}
+		if let Some(range) = tower_stats.air_range {
+			tower.insert(RangedShooterAir(range));
+		}
+		tower
+	}
+}
+
+// Which enemy in range a tower goes for
+#[derive(Component, Copy, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum Targeting {
+	// Furthest along the path
+	#[default]
+	First,
+	Last,
+	Strongest,
+	Weakest,
+	Closest,
+	Fastest,
+}
+
+impl Targeting {
+	pub const ALL: [Targeting; 6] = [
+		Targeting::First,
+		Targeting::Last,
+		Targeting::Strongest,
+		Targeting::Weakest,
+		Targeting::Closest,
+		Targeting::Fastest,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Targeting::First => "First",
+			Targeting::Last => "Last",
+			Targeting::Strongest => "Strong",
+			Targeting::Weakest => "Weak",
+			Targeting::Closest => "Close",
+			Targeting::Fastest => "Fast",
+		}
+	}
+
+	// Higher scores get picked first
+	fn score(&self, candidate: &Candidate, dist: f32) -> f32 {
 		match self {
-			Tower::Land => commands.spawn(land_tower(location)),
-			Tower::All => commands.spawn(all_tower(location)),
-			Tower::Fire => commands.spawn(all_tower(location)),
-			Tower::Water => commands.spawn(all_tower(location)),
-			Tower::Air => commands.spawn(all_tower(location)),
-			Tower::Laser => commands.spawn(all_tower(location)),
+			Targeting::First => candidate.progress,
+			Targeting::Last => -candidate.progress,
+			Targeting::Strongest => candidate.health as f32,
+			Targeting::Weakest => -candidate.health as f32,
+			Targeting::Closest => -dist,
+			Targeting::Fastest => candidate.speed,
 		}
 	}
 }
@@ -75,21 +164,126 @@ pub struct RangedShooterAir(f32);
 #[derive(Component, Debug, Deref, DerefMut)]
 pub struct AoE(f32);
 
+// Fraction of the damage lost at the edge of the AoE radius
+#[derive(Component, Debug, Deref, DerefMut)]
+pub struct Falloff(f32);
+
+#[derive(Debug, Copy, Clone)]
+pub struct Splash {
+	radius: f32,
+	falloff: f32,
+}
+
 #[derive(Component, Debug)]
 pub struct AttackSpeed(Timer);
 
 #[derive(Component, Debug)]
 pub struct Damage(i32);
 
+// Sets enemies on fire, dealing damage every tick for the duration
+#[derive(Component, Debug, Copy, Clone)]
+pub struct Burn {
+	damage: i32,
+	duration: f32,
+}
+
+// Slows enemies down to a fraction of their speed for the duration
+#[derive(Component, Debug, Copy, Clone)]
+pub struct Chill {
+	factor: f32,
+	duration: f32,
+}
+
+// Hitscan attack that stays locked onto one target while it's in range,
+// dealing its damage every attack tick instead of spawning bullets
+#[derive(Component, Debug, Default)]
+pub struct Beam {
+	target: Option<Entity>,
+}
+
+#[derive(Component, Debug)]
+pub struct BeamVisual;
+
+#[derive(Component, Debug)]
+pub struct Upgraded(pub i32);
+
+// Total money spent on a tower, including upgrades
 #[derive(Component, Debug)]
-pub struct Upgraded(i32);
+pub struct Invested(i32);
+
+// Keeps the stats themselves rather than replaying upgrades, so a restored
+// tower is exactly what was saved
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedTower {
+	tower: Tower,
+	location: [f32; 3],
+	level: i32,
+	invested: i32,
+	targeting: Targeting,
+	damage: i32,
+	attack_speed: f32,
+	land_range: Option<f32>,
+	air_range: Option<f32>,
+}
+
+impl SavedTower {
+	pub fn new(
+		(tower, trans, level, invested, targeting, damage, attack_speed, land, air): (
+			&Tower,
+			&Transform,
+			&Upgraded,
+			&Invested,
+			&Targeting,
+			&Damage,
+			&AttackSpeed,
+			Option<&RangedShooterLand>,
+			Option<&RangedShooterAir>,
+		),
+	) -> Self {
+		SavedTower {
+			tower: *tower,
+			location: trans.translation.to_array(),
+			level: level.0,
+			invested: invested.0,
+			targeting: *targeting,
+			damage: damage.0,
+			attack_speed: attack_speed.0.duration().as_secs_f32(),
+			land_range: land.map(|r| **r),
+			air_range: air.map(|r| **r),
+		}
+	}
+
+	pub fn restore(&self, commands: &mut Commands, height_map: &HeightMap, stats: &Stats) {
+		let location = Vec3::from_array(self.location);
+		let mut tower = self.tower.spawn(location, commands, stats);
+		tower.insert((
+			Upgraded(self.level),
+			Invested(self.invested),
+			self.targeting,
+			Damage(self.damage),
+			AttackSpeed(Timer::from_seconds(self.attack_speed, TimerMode::Repeating)),
+		));
+		if let Some(range) = self.land_range {
+			tower.insert(RangedShooterLand(range));
+		}
+		if let Some(range) = self.air_range {
+			tower.insert(RangedShooterAir(range));
+		}
+
+		let (x, z) = utils::to_map_space(height_map, location);
+		OCCUPIED_MAP.lock().unwrap()[x][z] = true;
+	}
+}
 
 #[derive(Bundle, Debug)]
 pub struct TowerBundle {
 	pub tower: Tower,
 	pub attack_speed: AttackSpeed,
 	pub damage: Damage,
+	pub damage_type: DamageType,
 	pub level: Upgraded,
+	pub invested: Invested,
+	pub targeting: Targeting,
 }
 
 #[derive(Component, Debug)]
@@ -97,11 +291,16 @@ pub struct Bullet {
 	pub target: Entity,
 	pub start_location: Vec3,
 	pub target_location: Vec3,
-	pub spawned_at: Instant,
+	// Ticked with the game clock so bullets keep up with the game speed
+	pub flight: Timer,
 	pub damage: i32,
+	pub damage_type: DamageType,
+	pub burn: Option<Burn>,
+	pub chill: Option<Chill>,
+	pub splash: Option<Splash>,
 }
 
-static BULLET_MODEL: OnceCell<(Handle<Mesh>, Handle<StandardMaterial>)> = OnceCell::new();
+static BULLET_MODEL: OnceCell<(Handle<Mesh>, [Handle<StandardMaterial>; 6])> = OnceCell::new();
 
 pub fn init_bullet_model(
 	mut meshes: ResMut<Assets<Mesh>>,
@@ -116,51 +315,77 @@ pub fn init_bullet_model(
 				}
 				.into(),
 			),
-			materials.add(Colour::rgb(1.0, 1.0, 1.0).into()),
+			Tower::ALL.map(|tower| materials.add(tower.bullet_colour().into())),
 		)
 	});
 }
 
-static LAND_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static ALL_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static WATER_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static FIRE_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
-static LASER_SCENE: OnceCell<Handle<Scene>> = OnceCell::new();
+static BEAM_MODEL: OnceCell<(Handle<Mesh>, Handle<StandardMaterial>)> = OnceCell::new();
 
-pub fn init_tower_models(asset_server: Res<AssetServer>) {
-	LAND_SCENE.get_or_init(|| asset_server.load("exported/Moai.gltf#Scene0"));
-	ALL_SCENE.get_or_init(|| asset_server.load("exported/Moai.gltf#Scene0"));
-	WATER_SCENE.get_or_init(|| asset_server.load("exported/Moai.gltf#Scene0"));
-	FIRE_SCENE.get_or_init(|| asset_server.load("exported/Moai.gltf#Scene0"));
-	LASER_SCENE.get_or_init(|| asset_server.load("exported/Moai.gltf#Scene0"));
+pub fn init_beam_model(
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	BEAM_MODEL.get_or_init(|| {
+		(
+			meshes.add(shape::Cube { size: 1.0 }.into()),
+			materials.add(StandardMaterial {
+				base_color: Tower::Laser.bullet_colour(),
+				emissive: Tower::Laser.bullet_colour(),
+				unlit: true,
+				..default()
+			}),
+		)
+	});
 }
 
 pub fn move_bullets(
 	mut commands: Commands,
-	mut bullets: Query<(&mut Transform, &Bullet, Entity)>,
-	mut enemies: Query<&mut Health, With<Enemy>>,
+	mut bullets: Query<(&mut Transform, &mut Bullet, Entity), Without<Enemy>>,
+	mut enemies: Query<(&Transform, Damageable, &mut Burning, Option<&mut Slowed>), With<Enemy>>,
+	mut kills: EventWriter<EnemyKilled>,
 	time: Res<Time>,
 ) {
-	let now = time.startup() + time.elapsed();
-	for (mut trans, bullet, entity) in bullets.iter_mut() {
-		let progress = ((now - bullet.spawned_at).as_secs_f32() / BULLET_TRAVEL_TIME.as_secs_f32())
-			.clamp(0.0, 1.0);
+	for (mut trans, mut bullet, entity) in bullets.iter_mut() {
+		let progress = bullet.flight.tick(time.delta()).percent();
 		let new_pos = bullet.start_location.lerp(bullet.target_location, progress);
 		trans.translation = new_pos;
 
 		if progress >= 1.0 {
 			commands.entity(entity).despawn_recursive();
 
-			let mut health = match enemies.get_mut(bullet.target) {
+			if let Some(splash) = bullet.splash {
+				for (enemy_pos, mut target, ..) in enemies.iter_mut() {
+					let dist = enemy_pos.translation.distance(bullet.target_location);
+					if target.entity == bullet.target || dist > splash.radius {
+						continue;
+					}
+					let scale = 1.0 - splash.falloff * dist / splash.radius;
+					let damage = (bullet.damage as f32 * scale) as i32;
+					enemies::damage_enemy(&mut target, damage, bullet.damage_type, &mut kills);
+				}
+			}
+
+			let (_, mut target, mut burning, slowed) = match enemies.get_mut(bullet.target) {
 				Ok(h) => h,
 				Err(_) => {
 					continue;
 				}
 			};
-			health.current -= bullet.damage;
+			enemies::damage_enemy(&mut target, bullet.damage, bullet.damage_type, &mut kills);
 
-			if health.current <= 0 {
-				commands.entity(bullet.target).despawn_recursive();
+			if let Some(burn) = bullet.burn {
+				burning.add_stack(burn.damage, burn.duration);
+			}
+
+			if let Some(chill) = bullet.chill {
+				enemies::slow_enemy(
+					&mut commands,
+					bullet.target,
+					slowed,
+					chill.factor,
+					chill.duration,
+				);
 			}
 		}
 	}
@@ -168,86 +393,160 @@ pub fn move_bullets(
 
 pub fn land_attack(
 	commands: Commands,
-	towers: Query<(
-		&Tower,
-		&Transform,
-		&RangedShooterLand,
-		&Damage,
-		&mut AttackSpeed,
-	)>,
+	towers: Query<
+		(
+			&Tower,
+			&Transform,
+			&RangedShooterLand,
+			&Damage,
+			&DamageType,
+			&mut AttackSpeed,
+			&Targeting,
+			Option<&Burn>,
+			Option<&Chill>,
+			Option<&AoE>,
+			Option<&Falloff>,
+		),
+		Without<Beam>,
+	>,
 	enemies: Query<
-		(Entity, &Transform, &Progress, &Speed, &PathSelection),
+		(
+			Entity,
+			&Transform,
+			&Progress,
+			&Speed,
+			&PathSelection,
+			&Health,
+		),
 		(With<Enemy>, Without<Air>),
 	>,
+	level_data: Res<LevelData>,
 	time: Res<Time>,
 ) {
-	ranged_attack(commands, towers, enemies, time)
+	ranged_attack(commands, towers, enemies, level_data, time)
 }
 
 pub fn air_attack(
 	commands: Commands,
-	towers: Query<(
-		&Tower,
-		&Transform,
-		&RangedShooterAir,
-		&Damage,
-		&mut AttackSpeed,
-	)>,
+	towers: Query<
+		(
+			&Tower,
+			&Transform,
+			&RangedShooterAir,
+			&Damage,
+			&DamageType,
+			&mut AttackSpeed,
+			&Targeting,
+			Option<&Burn>,
+			Option<&Chill>,
+			Option<&AoE>,
+			Option<&Falloff>,
+		),
+		Without<Beam>,
+	>,
 	enemies: Query<
-		(Entity, &Transform, &Progress, &Speed, &PathSelection),
+		(
+			Entity,
+			&Transform,
+			&Progress,
+			&Speed,
+			&PathSelection,
+			&Health,
+		),
 		(With<Enemy>, With<Air>),
 	>,
+	level_data: Res<LevelData>,
 	time: Res<Time>,
 ) {
-	ranged_attack(commands, towers, enemies, time)
+	ranged_attack(commands, towers, enemies, level_data, time)
 }
 
 fn ranged_attack<Range, Filter>(
 	mut commands: Commands,
-	mut towers: Query<(&Tower, &Transform, &Range, &Damage, &mut AttackSpeed)>,
-	mut enemies: Query<
-		(Entity, &Transform, &Progress, &Speed, &PathSelection),
+	mut towers: Query<
+		(
+			&Tower,
+			&Transform,
+			&Range,
+			&Damage,
+			&DamageType,
+			&mut AttackSpeed,
+			&Targeting,
+			Option<&Burn>,
+			Option<&Chill>,
+			Option<&AoE>,
+			Option<&Falloff>,
+		),
+		Without<Beam>,
+	>,
+	enemies: Query<
+		(
+			Entity,
+			&Transform,
+			&Progress,
+			&Speed,
+			&PathSelection,
+			&Health,
+		),
 		(With<Enemy>, Filter),
 	>,
+	level_data: Res<LevelData>,
 	time: Res<Time>,
 ) where
 	Range: Component + Deref<Target = f32>,
 	Filter: ReadOnlyWorldQuery,
 {
-	let (mesh, material) = BULLET_MODEL
+	let (mesh, bullet_materials) = BULLET_MODEL
 		.get()
 		.expect("Bullet model should've been initialised");
-	let spawned_at = time.startup() + time.elapsed();
 
-	for (_, tower_pos, tower_range, tower_dmg, mut tower_timer) in towers.iter_mut() {
+	for (
+		tower,
+		tower_pos,
+		tower_range,
+		tower_dmg,
+		damage_type,
+		mut tower_timer,
+		targeting,
+		burn,
+		chill,
+		aoe,
+		falloff,
+	) in towers.iter_mut()
+	{
 		if !tower_timer.0.tick(time.delta()).just_finished() {
 			continue;
 		}
 
 		let range: f32 = *tower_range.deref();
-		let Some(((entity, _, prog, speed, track), _)) = enemies
-			.iter_mut()
-			.map(|enemy| {
-				let dist = enemy.1.translation.distance(tower_pos.translation);
-				(enemy, dist)
-			})
-			.filter(|(_, dist)| *dist < range)
-			.min_by_key(|(_, dist)| {
-				// Cast distance to an integer for total ordering
-				(*dist * 1000.0) as i32
-			})
-		else {
+		let Some((entity, enemy_pos, prog, speed, track, _)) = pick_target(
+			tower_pos.translation,
+			*targeting,
+			enemies.iter().map(|enemy| {
+				let (_, trans, prog, speed, _, health) = enemy;
+				let candidate = Candidate {
+					pos: trans.translation,
+					range,
+					progress: prog.0,
+					health: health.current,
+					speed: speed.0,
+				};
+				(enemy, candidate)
+			}),
+		) else {
 			continue;
 		};
 
+		// Speed is already scaled down while slowed, so this still leads the target correctly
 		let target_progress = prog.0 + speed.0 * BULLET_TRAVEL_TIME.as_secs_f32();
-		let target_location =
-			easy::PATHS[track.0].interpolate(target_progress) + Vec3::new(0.0, 0.5, 0.0);
+		let mut target_location = level_data.paths[track.0].interpolate(target_progress);
+		// Paths run along the ground, keep the height flying enemies are at
+		target_location.y = enemy_pos.translation.y + 0.5;
 
 		commands.spawn((
 			PbrBundle {
 				mesh: mesh.clone(),
-				material: material.clone(),
+				material: bullet_materials[*tower as usize].clone(),
 				transform: Transform {
 					translation: tower_pos.translation,
 					..default()
@@ -258,20 +557,173 @@ fn ranged_attack<Range, Filter>(
 				target: entity,
 				start_location: tower_pos.translation + Vec3::new(0.0, 1.5, 0.0),
 				target_location,
-				spawned_at,
+				flight: Timer::new(BULLET_TRAVEL_TIME, TimerMode::Once),
 				damage: tower_dmg.0,
+				damage_type: *damage_type,
+				burn: burn.copied(),
+				chill: chill.copied(),
+				splash: aoe.map(|aoe| Splash {
+					radius: **aoe,
+					falloff: falloff.map_or(0.0, |f| **f),
+				}),
 			},
 		));
 	}
 }
 
-pub fn land_tower(location: Vec3) -> impl Bundle {
+// What a tower gets to know about an enemy when deciding what to attack
+#[derive(Debug, Copy, Clone)]
+struct Candidate {
+	pos: Vec3,
+	// How far away the tower can reach this enemy
+	range: f32,
+	progress: f32,
+	health: i32,
+	speed: f32,
+}
+
+// Picks the enemy to attack out of the candidates within range
+fn pick_target<T>(
+	tower_pos: Vec3,
+	targeting: Targeting,
+	enemies: impl Iterator<Item = (T, Candidate)>,
+) -> Option<T> {
+	enemies
+		.map(|(enemy, candidate)| {
+			let dist = candidate.pos.distance(tower_pos);
+			(enemy, candidate, dist)
+		})
+		.filter(|(_, candidate, dist)| *dist < candidate.range)
+		.max_by(|(_, a, a_dist), (_, b, b_dist)| {
+			targeting
+				.score(a, *a_dist)
+				.total_cmp(&targeting.score(b, *b_dist))
+		})
+		.map(|(enemy, ..)| enemy)
+}
+
+pub fn beam_attack(
+	mut towers: Query<
+		(
+			&GlobalTransform,
+			Option<&RangedShooterLand>,
+			Option<&RangedShooterAir>,
+			&Damage,
+			&DamageType,
+			&mut AttackSpeed,
+			&mut Beam,
+			&Targeting,
+			&Children,
+		),
+		With<Tower>,
+	>,
+	mut visuals: Query<
+		(&mut Transform, &mut Visibility),
+		(With<BeamVisual>, Without<Tower>, Without<Enemy>),
+	>,
+	mut enemies: Query<
+		(&Transform, Damageable, Option<&Air>, &Progress, &Speed),
+		(With<Enemy>, Without<Tower>),
+	>,
+	mut kills: EventWriter<EnemyKilled>,
+	time: Res<Time>,
+) {
+	for (
+		tower_pos,
+		land,
+		air,
+		tower_dmg,
+		damage_type,
+		mut tower_timer,
+		mut beam,
+		targeting,
+		children,
+	) in towers.iter_mut()
+	{
+		let range = |is_air: bool| {
+			if is_air {
+				air.map(|r| **r)
+			} else {
+				land.map(|r| **r)
+			}
+		};
+		let candidate = |(trans, target, is_air, prog, speed): (
+			&Transform,
+			DamageableReadOnlyItem,
+			Option<&Air>,
+			&Progress,
+			&Speed,
+		)| {
+			let candidate = Candidate {
+				pos: trans.translation,
+				range: range(is_air.is_some())?,
+				progress: prog.0,
+				health: target.health.current,
+				speed: speed.0,
+			};
+			(candidate.health > 0).then_some((target.entity, candidate))
+		};
+
+		// Only look for a new target once the old one dies or gets out of range
+		let locked = beam
+			.target
+			.and_then(|target| enemies.get(target).ok())
+			.and_then(candidate)
+			.filter(|(_, c)| c.pos.distance(tower_pos.translation()) < c.range)
+			.map(|(entity, _)| entity);
+		beam.target = locked.or_else(|| {
+			pick_target(
+				tower_pos.translation(),
+				*targeting,
+				enemies.iter().filter_map(candidate),
+			)
+		});
+
+		let Some(&child) = children.iter().find(|child| visuals.contains(**child)) else {
+			continue;
+		};
+		let (mut visual, mut visibility) = visuals.get_mut(child).unwrap();
+		let Some(target) = beam.target else {
+			*visibility = Visibility::Hidden;
+			continue;
+		};
+		let Ok((target_pos, mut target, ..)) = enemies.get_mut(target) else {
+			continue;
+		};
+
+		let start = tower_pos.translation() + Vec3::new(0.0, 1.5, 0.0);
+		let end = target_pos.translation + Vec3::new(0.0, 0.5, 0.0);
+		let beam_trans = Transform::from_translation((start + end) / 2.0)
+			.looking_at(end, Vec3::Y)
+			.with_scale(Vec3::new(0.05, 0.05, start.distance(end)));
+		*visual = GlobalTransform::from(beam_trans).reparented_to(tower_pos);
+		*visibility = Visibility::Visible;
+
+		if tower_timer.0.tick(time.delta()).just_finished() {
+			enemies::damage_enemy(&mut target, tower_dmg.0, *damage_type, &mut kills);
+		}
+	}
+}
+
+fn beam_visual() -> impl Bundle {
+	let (mesh, material) = BEAM_MODEL
+		.get()
+		.expect("Beam model should've been initialised");
+	(
+		PbrBundle {
+			mesh: mesh.clone(),
+			material: material.clone(),
+			visibility: Visibility::Hidden,
+			..default()
+		},
+		BeamVisual,
+	)
+}
+
+pub fn land_tower(location: Vec3, stats: &TowerStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: LAND_SCENE
-				.get()
-				.expect("Land scene should've been loaded")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(location.x, location.y, location.z)
 				.looking_to(Vec3::X, Vec3::Y),
 			..default()
@@ -279,21 +731,23 @@ pub fn land_tower(location: Vec3) -> impl Bundle {
 		ScreenSpaceAmbientOcclusionBundle { ..default() },
 		TowerBundle {
 			tower: Tower::Land,
-			attack_speed: AttackSpeed(Timer::from_seconds(0.8, TimerMode::Repeating)),
-			damage: Damage(30),
+			attack_speed: AttackSpeed(Timer::from_seconds(
+				stats.attack_speed,
+				TimerMode::Repeating,
+			)),
+			damage: Damage(stats.damage),
+			damage_type: DamageType::Physical,
 			level: Upgraded(0),
+			invested: Invested(stats.cost),
+			targeting: Targeting::First,
 		},
-		RangedShooterLand(5.0),
 	)
 }
 
-pub fn all_tower(location: Vec3) -> impl Bundle {
+pub fn all_tower(location: Vec3, stats: &TowerStats) -> impl Bundle {
 	(
 		SceneBundle {
-			scene: ALL_SCENE
-				.get()
-				.expect("All scene should've been loaded")
-				.clone(),
+			scene: stats.scene.clone(),
 			transform: Transform::from_xyz(location.x, location.y, location.z)
 				.looking_to(Vec3::X, Vec3::Y),
 			..default()
@@ -301,49 +755,339 @@ pub fn all_tower(location: Vec3) -> impl Bundle {
 		ScreenSpaceAmbientOcclusionBundle { ..default() },
 		TowerBundle {
 			tower: Tower::All,
-			attack_speed: AttackSpeed(Timer::from_seconds(0.15, TimerMode::Repeating)),
-			damage: Damage(30),
+			attack_speed: AttackSpeed(Timer::from_seconds(
+				stats.attack_speed,
+				TimerMode::Repeating,
+			)),
+			damage: Damage(stats.damage),
+			damage_type: DamageType::Physical,
 			level: Upgraded(0),
+			invested: Invested(stats.cost),
+			targeting: Targeting::First,
 		},
-		RangedShooterLand(5.0),
-		RangedShooterAir(5.0),
+	)
+}
+
+pub fn fire_tower(location: Vec3, stats: &TowerStats) -> impl Bundle {
+	(
+		SceneBundle {
+			scene: stats.scene.clone(),
+			transform: Transform::from_xyz(location.x, location.y, location.z)
+				.looking_to(Vec3::X, Vec3::Y),
+			..default()
+		},
+		ScreenSpaceAmbientOcclusionBundle { ..default() },
+		TowerBundle {
+			tower: Tower::Fire,
+			attack_speed: AttackSpeed(Timer::from_seconds(
+				stats.attack_speed,
+				TimerMode::Repeating,
+			)),
+			damage: Damage(stats.damage),
+			damage_type: DamageType::Fire,
+			level: Upgraded(0),
+			invested: Invested(stats.cost),
+			targeting: Targeting::First,
+		},
+		Burn {
+			damage: 3,
+			duration: 3.0,
+		},
+		AoE(1.5),
+		Falloff(0.5),
+	)
+}
+
+pub fn water_tower(location: Vec3, stats: &TowerStats) -> impl Bundle {
+	(
+		SceneBundle {
+			scene: stats.scene.clone(),
+			transform: Transform::from_xyz(location.x, location.y, location.z)
+				.looking_to(Vec3::X, Vec3::Y),
+			..default()
+		},
+		ScreenSpaceAmbientOcclusionBundle { ..default() },
+		TowerBundle {
+			tower: Tower::Water,
+			attack_speed: AttackSpeed(Timer::from_seconds(
+				stats.attack_speed,
+				TimerMode::Repeating,
+			)),
+			damage: Damage(stats.damage),
+			damage_type: DamageType::Water,
+			level: Upgraded(0),
+			invested: Invested(stats.cost),
+			targeting: Targeting::First,
+		},
+		Chill {
+			factor: 0.5,
+			duration: 2.0,
+		},
+		AoE(1.0),
+	)
+}
+
+pub fn air_tower(location: Vec3, stats: &TowerStats) -> impl Bundle {
+	(
+		SceneBundle {
+			scene: stats.scene.clone(),
+			transform: Transform::from_xyz(location.x, location.y, location.z)
+				.looking_to(Vec3::X, Vec3::Y),
+			..default()
+		},
+		ScreenSpaceAmbientOcclusionBundle { ..default() },
+		TowerBundle {
+			tower: Tower::Air,
+			attack_speed: AttackSpeed(Timer::from_seconds(
+				stats.attack_speed,
+				TimerMode::Repeating,
+			)),
+			damage: Damage(stats.damage),
+			damage_type: DamageType::Physical,
+			level: Upgraded(0),
+			invested: Invested(stats.cost),
+			targeting: Targeting::First,
+		},
+	)
+}
+
+pub fn laser_tower(location: Vec3, stats: &TowerStats) -> impl Bundle {
+	(
+		SceneBundle {
+			scene: stats.scene.clone(),
+			transform: Transform::from_xyz(location.x, location.y, location.z)
+				.looking_to(Vec3::X, Vec3::Y),
+			..default()
+		},
+		ScreenSpaceAmbientOcclusionBundle { ..default() },
+		TowerBundle {
+			tower: Tower::Laser,
+			attack_speed: AttackSpeed(Timer::from_seconds(
+				stats.attack_speed,
+				TimerMode::Repeating,
+			)),
+			damage: Damage(stats.damage),
+			damage_type: DamageType::Energy,
+			level: Upgraded(0),
+			invested: Invested(stats.cost),
+			targeting: Targeting::First,
+		},
+		Beam::default(),
 	)
 }
 
 #[derive(Resource)]
 pub struct Banking {
 	pub selection: Option<Tower>,
+	pub selling: bool,
+	// A placed tower, picked by clicking on it
+	pub selected: Option<Entity>,
 	pub balance: i32,
+	// Fraction of the invested money paid back when selling
+	pub refund: f32,
+}
+
+impl Banking {
+	pub fn new() -> Self {
+		Banking {
+			selection: Some(Tower::Land),
+			selling: false,
+			selected: None,
+			balance: 200,
+			refund: 0.7,
+		}
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PlacementError {
+	OutOfBounds,
+	Path,
+	Water,
+	Occupied,
+	Funds,
+}
+
+impl PlacementError {
+	pub fn message(&self) -> &'static str {
+		match self {
+			PlacementError::OutOfBounds => "Outside the map",
+			PlacementError::Path => "Can't build on the path",
+			PlacementError::Water => "Can't build on water",
+			PlacementError::Occupied => "There's already a tower there",
+			PlacementError::Funds => "Not enough money",
+		}
+	}
+}
+
+#[derive(Event, Debug)]
+pub struct PlacementRejected(pub PlacementError);
+
+fn check_placement(
+	level_data: &LevelData,
+	location: Vec3,
+) -> Result<(usize, usize), PlacementError> {
+	if location.y < 0.0
+		|| !(-20.0..20.0).contains(&location.z)
+		|| !(-16.0..16.0).contains(&location.x)
+	{
+		return Err(PlacementError::OutOfBounds);
+	}
+
+	let height_map = &level_data.height_map;
+	let (x, z) = utils::to_map_space(height_map, location);
+	if level_data
+		.paths
+		.iter()
+		.any(|path| path.covers(height_map, (x, z)))
+	{
+		return Err(PlacementError::Path);
+	}
+	if height_map[x][z] == levels::WATER {
+		return Err(PlacementError::Water);
+	}
+	if OCCUPIED_MAP.lock().unwrap()[x][z] {
+		return Err(PlacementError::Occupied);
+	}
+
+	Ok((x, z))
 }
 
 pub fn spawn_tower(
 	mut clicks: EventReader<Click>,
 	mut commands: Commands,
-	asset_server: Res<AssetServer>,
 	mut tower_selection: ResMut<Banking>,
-	mut materials: ResMut<Assets<StandardMaterial>>,
-	mut balance_label: Query<&mut Text, With<BalanceLabel>>,
+	mut rejections: EventWriter<PlacementRejected>,
+	level_data: Res<LevelData>,
+	stats: Res<Stats>,
 ) {
-	let mut balance_label = balance_label.get_single_mut().unwrap();
 	for location in clicks.iter().filter_map(|ev| ev.world()) {
-		if location.y < 0.0 {
-			continue;
-		}
-
 		let Some(selection) = tower_selection.selection else {
 			continue;
 		};
 
-		let cost = selection.cost();
+		let (x, z) = match check_placement(&level_data, location) {
+			Ok(cell) => cell,
+			Err(err) => {
+				rejections.send(PlacementRejected(err));
+				continue;
+			}
+		};
+
+		let cost = stats.tower(selection).cost;
 		if tower_selection.balance < cost {
+			rejections.send(PlacementRejected(PlacementError::Funds));
 			continue;
 		}
 
-		selection.spawn(location, &asset_server, &mut commands, &mut materials);
+		selection.spawn(location, &mut commands, &stats);
+		OCCUPIED_MAP.lock().unwrap()[x][z] = true;
 
 		tower_selection.selection = None;
 		tower_selection.balance -= cost;
+	}
+}
+
+// Towers get despawned along with the level, free up their cells as well
+pub fn clear_occupied_map() {
+	*OCCUPIED_MAP.lock().unwrap() = [[false; 20]; 16];
+}
+
+pub fn sell_tower(
+	mut clicks: EventReader<Click>,
+	mut commands: Commands,
+	mut banking: ResMut<Banking>,
+	towers: Query<(Entity, &Transform, &Invested), With<Tower>>,
+	level_data: Res<LevelData>,
+) {
+	let height_map = &level_data.height_map;
+	for location in clicks.iter().filter_map(|ev| ev.world()) {
+		if !banking.selling {
+			continue;
+		}
+
+		let cell = utils::to_map_space(height_map, location);
+		let Some((entity, _, invested)) = towers
+			.iter()
+			.find(|(_, trans, _)| utils::to_map_space(height_map, trans.translation) == cell)
+		else {
+			continue;
+		};
 
-		balance_label.sections[0].value = format!("{}", tower_selection.balance);
+		commands.entity(entity).despawn_recursive();
+		OCCUPIED_MAP.lock().unwrap()[cell.0][cell.1] = false;
+
+		banking.selling = false;
+		banking.balance += (invested.0 as f32 * banking.refund) as i32;
+		if banking.selected == Some(entity) {
+			banking.selected = None;
+		}
+	}
+}
+
+pub fn select_tower(
+	mut clicks: EventReader<Click>,
+	mut banking: ResMut<Banking>,
+	volcano: Res<Volcano>,
+	towers: Query<(Entity, &Transform), With<Tower>>,
+	level_data: Res<LevelData>,
+) {
+	let height_map = &level_data.height_map;
+	for location in clicks.iter().filter_map(|ev| ev.world()) {
+		if banking.selection.is_some() || banking.selling || volcano.armed {
+			continue;
+		}
+
+		let cell = utils::to_map_space(height_map, location);
+		banking.selected = towers
+			.iter()
+			.find(|(_, trans)| utils::to_map_space(height_map, trans.translation) == cell)
+			.map(|(entity, _)| entity);
+	}
+}
+
+#[derive(Event, Debug)]
+pub struct UpgradeTower(pub Entity);
+
+pub fn upgrade_tower(
+	mut upgrades: EventReader<UpgradeTower>,
+	mut banking: ResMut<Banking>,
+	mut towers: Query<(
+		&Tower,
+		&mut Upgraded,
+		&mut Invested,
+		&mut Damage,
+		&mut AttackSpeed,
+		Option<&mut RangedShooterLand>,
+		Option<&mut RangedShooterAir>,
+	)>,
+	stats: Res<Stats>,
+) {
+	for UpgradeTower(entity) in upgrades.iter() {
+		let Ok((tower, mut level, mut invested, mut damage, mut attack_speed, land, air)) =
+			towers.get_mut(*entity)
+		else {
+			continue;
+		};
+		let Some(cost) = tower.upgrade_cost(level.0, &stats) else {
+			continue;
+		};
+		if banking.balance < cost {
+			continue;
+		}
+
+		banking.balance -= cost;
+		level.0 += 1;
+		invested.0 += cost;
+
+		damage.0 = damage.0 * 3 / 2;
+		let duration = attack_speed.0.duration().mul_f32(0.8);
+		attack_speed.0.set_duration(duration);
+		if let Some(mut range) = land {
+			**range += 1.0;
+		}
+		if let Some(mut range) = air {
+			**range += 1.0;
+		}
 	}
 }
@@ -1,13 +1,18 @@
 use std::sync::Mutex;
 
-use bevy::prelude::*;
+use bevy::{ecs::query::Has, prelude::*};
 use once_cell::sync::OnceCell;
 use variantly::Variantly;
 
 use crate::gameplay::{
 	cursor::Cursor,
-	towers::{Banking, Tower},
+	enemies::{Boss, Health, Immune},
+	level_data::LevelData,
+	levels::{AppState, GameState, LevelEntity, WaveCleared, WaveStarted},
+	menu::{MenuButton, GAME_SPEEDS},
+	towers::{Banking, PlacementRejected, Targeting, Tower, UpgradeTower, Upgraded},
 	utils::{self, VisualMarker},
+	volcano::Volcano,
 };
 
 type Colour = Color;
@@ -17,6 +22,32 @@ type Colour = Color;
 #[derive(Component, Debug)]
 pub struct BalanceLabel;
 
+#[derive(Component, Debug)]
+pub struct LivesLabel;
+
+#[derive(Component, Debug)]
+pub struct StatusLabel;
+
+#[derive(Component, Debug)]
+pub struct CooldownOverlay;
+
+#[derive(Component, Debug)]
+pub struct BossBar;
+
+#[derive(Component, Debug)]
+pub struct BossBarFill;
+
+// Full screen message shown when the game is paused or over
+#[derive(Component, Debug)]
+pub struct Banner;
+
+// Shows the selected tower and lets the player pick what it targets
+#[derive(Component, Debug)]
+pub struct InspectPanel;
+
+#[derive(Component, Debug)]
+pub struct InspectLabel;
+
 #[derive(Component, Copy, Clone, Debug, PartialEq)]
 pub enum ClickType {
 	Buy(Tower),
@@ -24,6 +55,9 @@ pub enum ClickType {
 	Sell,
 	Upgrade,
 	Next,
+	Target(Targeting),
+	Pause,
+	Speed(f32),
 }
 
 static ONE_TEXTURE: OnceCell<Handle<Image>> = OnceCell::new();
@@ -58,13 +92,16 @@ pub fn init_textures(asset_server: Res<AssetServer>) {
 
 pub fn setup_ui(mut commands: Commands) {
 	commands
-		.spawn(NodeBundle {
-			style: Style {
-				flex_direction: FlexDirection::Column,
+		.spawn((
+			NodeBundle {
+				style: Style {
+					flex_direction: FlexDirection::Column,
+					..default()
+				},
 				..default()
 			},
-			..default()
-		})
+			LevelEntity,
+		))
 		.with_children(|p| {
 			p.spawn(NodeBundle {
 				style: Style {
@@ -120,7 +157,37 @@ pub fn setup_ui(mut commands: Commands) {
 					SIX_TEXTURE.get().unwrap().clone(),
 					ClickType::Buy(Tower::Laser),
 				);
-				spawn_image(VOLCANO_TEXTURE.get().unwrap().clone(), ClickType::Volcano);
+				parent
+					.spawn((
+						ButtonBundle {
+							image: UiImage {
+								texture: VOLCANO_TEXTURE.get().unwrap().clone(),
+								..default()
+							},
+							style: Style {
+								width: Val::Px(SIZE),
+								height: Val::Px(SIZE),
+								align_items: AlignItems::End,
+								..Default::default()
+							},
+							..default()
+						},
+						ClickType::Volcano,
+					))
+					.with_children(|button| {
+						button.spawn((
+							NodeBundle {
+								style: Style {
+									width: Val::Percent(100.0),
+									height: Val::Percent(0.0),
+									..default()
+								},
+								background_color: Colour::rgba(0.0, 0.0, 0.0, 0.6).into(),
+								..default()
+							},
+							CooldownOverlay,
+						));
+					});
 				parent.spawn((
 					TextBundle {
 						text: Text::from_section(
@@ -143,6 +210,44 @@ pub fn setup_ui(mut commands: Commands) {
 					},
 					BalanceLabel,
 				));
+				parent.spawn((
+					TextBundle {
+						text: Text::from_section(
+							"0",
+							TextStyle {
+								font_size: SIZE - 12.0,
+								color: Colour::RED,
+								..default()
+							},
+						),
+						style: Style {
+							height: Val::Px(SIZE),
+							min_width: Val::Px(SIZE),
+							align_items: AlignItems::Center,
+							justify_items: JustifyItems::Center,
+							..default()
+						},
+						background_color: Colour::WHITE.into(),
+						..default()
+					},
+					LivesLabel,
+				));
+				parent.spawn((
+					TextBundle::from_section(
+						"",
+						TextStyle {
+							font_size: SIZE / 2.0,
+							color: Colour::WHITE,
+							..default()
+						},
+					)
+					.with_style(Style {
+						align_self: AlignSelf::Center,
+						margin: UiRect::left(Val::Px(12.0)),
+						..default()
+					}),
+					StatusLabel,
+				));
 			});
 			p.spawn(NodeBundle {
 				style: Style {
@@ -155,47 +260,416 @@ pub fn setup_ui(mut commands: Commands) {
 				..Default::default()
 			})
 			.with_children(|parent| {
-				let mut spawn_image = |texture| {
-					let width = Val::Px(64.0);
-					parent.spawn((
-						ButtonBundle {
-							image: UiImage {
-								texture,
+				spawn_text_button(parent, "Next", ClickType::Next);
+				spawn_text_button(parent, "Sell", ClickType::Sell);
+				spawn_text_button(parent, "Upgrade", ClickType::Upgrade);
+				spawn_text_button(parent, "Pause", ClickType::Pause);
+				for speed in GAME_SPEEDS {
+					spawn_text_button(parent, &format!("{speed}x"), ClickType::Speed(speed));
+				}
+				parent
+					.spawn((
+						NodeBundle {
+							style: Style {
+								flex_direction: FlexDirection::Row,
+								align_items: AlignItems::Center,
+								margin: UiRect::left(Val::Px(24.0)),
+								display: Display::None,
 								..default()
 							},
+							..default()
+						},
+						InspectPanel,
+					))
+					.with_children(|panel| {
+						panel.spawn((
+							TextBundle::from_section(
+								"",
+								TextStyle {
+									font_size: BUTTON_SIZE / 2.0,
+									color: Colour::WHITE,
+									..default()
+								},
+							)
+							.with_style(Style {
+								margin: UiRect::right(Val::Px(12.0)),
+								..default()
+							}),
+							InspectLabel,
+						));
+						for targeting in Targeting::ALL {
+							spawn_text_button(
+								panel,
+								targeting.name(),
+								ClickType::Target(targeting),
+							);
+						}
+					});
+			});
+		});
+}
+
+const BUTTON_SIZE: f32 = 64.0;
+
+// Height of the HUD strips along the top and bottom of the window
+const HUD_HEIGHT: f32 = 64.0;
+
+// Whether a point in window coordinates is over the HUD rather than the level
+pub fn over_hud(p: Vec2, window_height: f32) -> bool {
+	p.y <= HUD_HEIGHT || p.y >= window_height - HUD_HEIGHT
+}
+
+pub fn spawn_text_button(parent: &mut ChildBuilder, text: &str, button: impl Bundle) {
+	let width = Val::Px(BUTTON_SIZE);
+	parent
+		.spawn((
+			ButtonBundle {
+				style: Style {
+					min_width: width,
+					height: width,
+					padding: UiRect::horizontal(Val::Px(8.0)),
+					align_items: AlignItems::Center,
+					justify_content: JustifyContent::Center,
+					..Default::default()
+				},
+				background_color: Colour::WHITE.into(),
+				..default()
+			},
+			button,
+		))
+		.with_children(|button| {
+			button.spawn(TextBundle::from_section(
+				text,
+				TextStyle {
+					font_size: BUTTON_SIZE - 24.0,
+					color: Colour::BLACK,
+					..default()
+				},
+			));
+		});
+}
+
+pub fn setup_boss_bar(mut commands: Commands) {
+	commands
+		.spawn((
+			NodeBundle {
+				style: Style {
+					position_type: PositionType::Absolute,
+					top: Val::Px(72.0),
+					left: Val::Percent(20.0),
+					width: Val::Percent(60.0),
+					height: Val::Px(32.0),
+					align_items: AlignItems::Center,
+					display: Display::None,
+					..default()
+				},
+				..default()
+			},
+			BossBar,
+			LevelEntity,
+		))
+		.with_children(|parent| {
+			parent.spawn(ImageBundle {
+				image: UiImage {
+					texture: BOSS_TEXTURE.get().unwrap().clone(),
+					..default()
+				},
+				style: Style {
+					width: Val::Px(32.0),
+					height: Val::Px(32.0),
+					..default()
+				},
+				..default()
+			});
+			parent
+				.spawn(NodeBundle {
+					style: Style {
+						flex_grow: 1.0,
+						height: Val::Px(16.0),
+						..default()
+					},
+					background_color: Colour::rgba(0.0, 0.0, 0.0, 0.6).into(),
+					..default()
+				})
+				.with_children(|track| {
+					track.spawn((
+						NodeBundle {
 							style: Style {
-								width,
-								height: width,
-								..Default::default()
+								width: Val::Percent(100.0),
+								height: Val::Percent(100.0),
+								..default()
 							},
+							background_color: Colour::RED.into(),
 							..default()
 						},
-						ClickType::Next,
+						BossBarFill,
 					));
-				};
-				spawn_image(VOLCANO_TEXTURE.get().unwrap().clone());
-			});
+				});
 		});
 }
 
 pub fn run_shop(
 	buttons: Query<(&Interaction, &ClickType), (With<Button>, Changed<Interaction>)>,
 	mut banking: ResMut<Banking>,
+	mut upgrades: EventWriter<UpgradeTower>,
+	mut volcano: ResMut<Volcano>,
+	mut targeting: Query<&mut Targeting>,
 ) {
 	for (_, ct) in buttons.iter().filter(|(&i, _)| i == Interaction::Pressed) {
 		println!("{:?}", ct);
 		match ct {
 			ClickType::Buy(t) => {
 				banking.selection = Some(*t);
+				banking.selling = false;
+				banking.selected = None;
+				volcano.armed = false;
+			}
+			ClickType::Volcano => {
+				banking.selection = None;
+				banking.selling = false;
+				banking.selected = None;
+				volcano.armed = volcano.ready();
+			}
+			ClickType::Sell => {
+				banking.selection = None;
+				banking.selling = true;
+				banking.selected = None;
+				volcano.armed = false;
 			}
-			ClickType::Volcano => todo!(),
-			ClickType::Sell => todo!(),
-			ClickType::Upgrade => todo!(),
-			ClickType::Next => todo!(),
+			ClickType::Upgrade => {
+				if let Some(tower) = banking.selected {
+					upgrades.send(UpgradeTower(tower));
+				}
+			}
+			ClickType::Target(mode) => {
+				if let Some(mut targeting) = banking
+					.selected
+					.and_then(|tower| targeting.get_mut(tower).ok())
+				{
+					*targeting = *mode;
+				}
+			}
+			ClickType::Next | ClickType::Pause | ClickType::Speed(_) => {}
 		}
 	}
 }
 
+// The HUD buttons that drive the game rather than the shop
+pub fn run_game_controls(
+	buttons: Query<(&Interaction, &ClickType), (With<Button>, Changed<Interaction>)>,
+	mut level: ResMut<GameState>,
+	level_data: Res<LevelData>,
+	mut wave_started: EventWriter<WaveStarted>,
+	mut time: ResMut<Time>,
+	mut next_state: ResMut<NextState<AppState>>,
+) {
+	for (_, ct) in buttons.iter().filter(|(&i, _)| i == Interaction::Pressed) {
+		match ct {
+			ClickType::Next => {
+				if let Some(number) = level.next_wave(&level_data.waves) {
+					wave_started.send(WaveStarted(number));
+				}
+			}
+			ClickType::Pause => next_state.set(AppState::Paused),
+			ClickType::Speed(speed) => time.set_relative_speed(*speed),
+			_ => {}
+		}
+	}
+}
+
+pub fn show_volcano_cooldown(
+	volcano: Res<Volcano>,
+	mut overlay: Query<&mut Style, With<CooldownOverlay>>,
+) {
+	let Ok(mut overlay) = overlay.get_single_mut() else {
+		return;
+	};
+	overlay.height = Val::Percent(volcano.cooldown.percent_left() * 100.0);
+}
+
+pub fn show_boss_health(
+	bosses: Query<(&Health, Has<Immune>), With<Boss>>,
+	mut bar: Query<&mut Style, With<BossBar>>,
+	mut fill: Query<(&mut Style, &mut BackgroundColor), (With<BossBarFill>, Without<BossBar>)>,
+) {
+	let (Ok(mut bar), Ok((mut fill, mut colour))) = (bar.get_single_mut(), fill.get_single_mut())
+	else {
+		return;
+	};
+	let Some((health, immune)) = bosses.iter().next() else {
+		bar.display = Display::None;
+		return;
+	};
+
+	bar.display = Display::Flex;
+	let ratio = health.current.max(0) as f32 / health.max as f32;
+	fill.width = Val::Percent(ratio * 100.0);
+	*colour = if immune {
+		Colour::GRAY.into()
+	} else {
+		Colour::RED.into()
+	};
+}
+
+pub fn show_inspect_panel(
+	banking: Res<Banking>,
+	towers: Query<(&Tower, &Upgraded, &Targeting)>,
+	mut panel: Query<&mut Style, With<InspectPanel>>,
+	mut label: Query<&mut Text, With<InspectLabel>>,
+	mut buttons: Query<(&ClickType, &mut BackgroundColor)>,
+) {
+	let (Ok(mut panel), Ok(mut label)) = (panel.get_single_mut(), label.get_single_mut()) else {
+		return;
+	};
+	// The selected tower might have been sold since
+	let Some((tower, level, targeting)) = banking.selected.and_then(|t| towers.get(t).ok()) else {
+		panel.display = Display::None;
+		return;
+	};
+
+	panel.display = Display::Flex;
+	label.sections[0].value = format!("{} Lv{}", tower.name(), level.0 + 1);
+	for (ct, mut colour) in buttons.iter_mut() {
+		let ClickType::Target(mode) = ct else {
+			continue;
+		};
+		*colour = if mode == targeting {
+			Colour::GOLD.into()
+		} else {
+			Colour::WHITE.into()
+		};
+	}
+}
+
+pub fn show_game_speed(time: Res<Time>, mut buttons: Query<(&ClickType, &mut BackgroundColor)>) {
+	for (ct, mut colour) in buttons.iter_mut() {
+		let ClickType::Speed(speed) = ct else {
+			continue;
+		};
+		*colour = if *speed == time.relative_speed() {
+			Colour::GOLD.into()
+		} else {
+			Colour::WHITE.into()
+		};
+	}
+}
+
+pub fn update_balance_label(
+	banking: Res<Banking>,
+	mut balance_label: Query<&mut Text, With<BalanceLabel>>,
+) {
+	if !banking.is_changed() {
+		return;
+	}
+	let Ok(mut balance_label) = balance_label.get_single_mut() else {
+		return;
+	};
+	balance_label.sections[0].value = format!("{}", banking.balance);
+}
+
+pub fn update_lives_label(
+	level: Res<GameState>,
+	mut lives_label: Query<&mut Text, With<LivesLabel>>,
+) {
+	if !level.is_changed() {
+		return;
+	}
+	let Ok(mut lives_label) = lives_label.get_single_mut() else {
+		return;
+	};
+	lives_label.sections[0].value = format!("{}", level.lives);
+}
+
+pub fn update_status_label(
+	mut rejections: EventReader<PlacementRejected>,
+	mut wave_started: EventReader<WaveStarted>,
+	mut wave_cleared: EventReader<WaveCleared>,
+	banking: Res<Banking>,
+	mut status_label: Query<&mut Text, With<StatusLabel>>,
+) {
+	let Ok(mut status_label) = status_label.get_single_mut() else {
+		return;
+	};
+	if let Some(PlacementRejected(err)) = rejections.iter().last() {
+		status_label.sections[0].value = err.message().to_string();
+	} else if let Some(WaveCleared(number)) = wave_cleared.iter().last() {
+		status_label.sections[0].value = format!("Wave {} cleared", number + 1);
+	} else if let Some(WaveStarted(number)) = wave_started.iter().last() {
+		status_label.sections[0].value = format!("Wave {}", number + 1);
+	} else if banking.is_changed() {
+		status_label.sections[0].value.clear();
+	}
+}
+
+pub fn show_game_over(mut commands: Commands) {
+	spawn_banner(
+		&mut commands,
+		"Game Over",
+		Colour::RED,
+		&[("Menu", MenuButton::Menu)],
+	);
+}
+
+pub fn show_level_end(mut commands: Commands) {
+	spawn_banner(
+		&mut commands,
+		"Victory",
+		Colour::GOLD,
+		&[("Menu", MenuButton::Menu)],
+	);
+}
+
+pub fn show_pause(mut commands: Commands) {
+	spawn_banner(
+		&mut commands,
+		"Paused",
+		Colour::WHITE,
+		&[
+			("Resume", MenuButton::Resume),
+			("Save", MenuButton::Save),
+			("Menu", MenuButton::Menu),
+		],
+	);
+}
+
+fn spawn_banner(
+	commands: &mut Commands,
+	text: &str,
+	colour: Colour,
+	buttons: &[(&str, MenuButton)],
+) {
+	commands
+		.spawn((
+			NodeBundle {
+				style: Style {
+					position_type: PositionType::Absolute,
+					width: Val::Percent(100.0),
+					height: Val::Percent(100.0),
+					flex_direction: FlexDirection::Column,
+					align_items: AlignItems::Center,
+					justify_content: JustifyContent::Center,
+					row_gap: Val::Px(16.0),
+					..default()
+				},
+				..default()
+			},
+			Banner,
+		))
+		.with_children(|parent| {
+			parent.spawn(TextBundle::from_section(
+				text,
+				TextStyle {
+					font_size: 128.0,
+					color: colour,
+					..default()
+				},
+			));
+			for (text, button) in buttons {
+				spawn_text_button(parent, text, *button);
+			}
+		});
+}
+
 // ------------------------------ CLICKS ---------------------------------
 
 #[derive(Copy, Clone, PartialEq, Event, Variantly)]
@@ -212,6 +686,7 @@ pub fn generate_clicks(
 		(&Camera, &GlobalTransform),
 		(With<Camera3d>, Without<Cursor>, Without<VisualMarker>),
 	>,
+	level_data: Res<LevelData>,
 ) {
 	static CURSOR_POS: Mutex<Vec2> = Mutex::new(Vec2::ZERO);
 
@@ -226,12 +701,13 @@ pub fn generate_clicks(
 		*cur_ref = p;
 	}
 	if button.just_released(MouseButton::Left) && p != Vec2::ZERO && *cur_ref == p {
-		if p.y <= 64.0 {
+		let height = windows.get_single().map_or(f32::MAX, Window::height);
+		if over_hud(p, height) {
 			ev.send(Click::Gui(p));
 		} else {
 			let raw_ray = utils::get_world_pos(p, &mut cam_query)
 				.expect("How can a click be generated if the cursor isn't over the window?");
-			let p3d = utils::to_grid_with_height(raw_ray);
+			let p3d = utils::to_grid_with_height(&level_data.height_map, raw_ray);
 			dbg!(p3d);
 			ev.send(Click::World(p3d));
 		}
@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 
-use crate::{
-	easy,
-	gameplay::cursor::{Cursor, SquareHighlight},
+use crate::gameplay::{
+	cursor::{Cursor, Range, SquareHighlight},
+	levels::{HeightMap, LevelEntity},
 };
 
 type Colour = Color;
@@ -17,9 +17,9 @@ pub fn round_to_grid(v: Vec3) -> Vec3 {
 	Vec3::new(f(v.x), v.y, f(v.z))
 }
 
-pub fn to_map_space(Vec3 { x, y: _, z }: Vec3) -> (usize, usize) {
-	let height = easy::HEIGHT_MAP.len() - 1;
-	let width = easy::HEIGHT_MAP[0].len() - 1;
+pub fn to_map_space(height_map: &HeightMap, Vec3 { x, y: _, z }: Vec3) -> (usize, usize) {
+	let height = height_map.len() - 1;
+	let width = height_map[0].len() - 1;
 
 	let d_x = (((x + height as f32) / 2.0).round() as usize)
 		.min(height)
@@ -30,16 +30,22 @@ pub fn to_map_space(Vec3 { x, y: _, z }: Vec3) -> (usize, usize) {
 	(d_x, d_z)
 }
 
-pub fn with_height(v: Vec3) -> Vec3 {
-	let (d_x, d_z) = to_map_space(v);
-	let height_data = easy::HEIGHT_MAP[d_x][d_z];
+pub fn with_height(height_map: &HeightMap, v: Vec3) -> Vec3 {
+	let (d_x, d_z) = to_map_space(height_map, v);
+	let height_data = height_map[d_x][d_z];
 	let d_y = 1.0 - height_data as f32 / 10.0;
 
 	Vec3::new(v.x, d_y, v.z)
 }
 
-pub fn to_grid_with_height(v: Vec3) -> Vec3 {
-	with_height(round_to_grid(v))
+pub fn to_grid_with_height(height_map: &HeightMap, v: Vec3) -> Vec3 {
+	with_height(height_map, round_to_grid(v))
+}
+
+pub fn despawn_with<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+	for entity in query.iter() {
+		commands.entity(entity).despawn_recursive();
+	}
 }
 
 pub fn get_world_pos(
@@ -71,6 +77,7 @@ pub fn spawn_cursors(
 			..default()
 		},
 		Cursor,
+		LevelEntity,
 	));
 	commands.spawn((
 		SceneBundle {
@@ -79,20 +86,26 @@ pub fn spawn_cursors(
 			..default()
 		},
 		SquareHighlight,
+		LevelEntity,
 	));
-	commands.spawn(PbrBundle {
-		mesh: asset_server.load("exported/Range.gltf#Mesh0/Primitive0"),
-		material: materials.add(StandardMaterial {
-			alpha_mode: AlphaMode::Blend,
-			base_color: Colour::rgba(0.8, 0.8, 0.8, 0.6),
-			unlit: true,
-			double_sided: true,
-			cull_mode: None,
+	commands.spawn((
+		PbrBundle {
+			mesh: asset_server.load("exported/Range.gltf#Mesh0/Primitive0"),
+			material: materials.add(StandardMaterial {
+				alpha_mode: AlphaMode::Blend,
+				base_color: Colour::rgba(0.8, 0.8, 0.8, 0.6),
+				unlit: true,
+				double_sided: true,
+				cull_mode: None,
+				..default()
+			}),
+			transform: Transform::from_xyz(0.0, 1.0, 0.0),
+			visibility: Visibility::Hidden,
 			..default()
-		}),
-		transform: Transform::from_xyz(0.0, 1.0, 0.0),
-		..default()
-	});
+		},
+		Range,
+		LevelEntity,
+	));
 }
 
 pub fn spawn_axes(
@@ -101,30 +114,42 @@ pub fn spawn_axes(
 	materials: &mut ResMut<Assets<StandardMaterial>>,
 ) {
 	let cube = meshes.add(Mesh::from(shape::Cube { size: 1.0 }));
-	commands.spawn(PbrBundle {
-		mesh: cube.clone(),
-		material: materials.add(Colour::rgb(0.0, 0.0, 0.0).into()),
-		transform: Transform::from_xyz(0.0, 6.0, 0.0).with_scale((0.5, 0.5, 0.5).into()),
-		..default()
-	});
-	commands.spawn(PbrBundle {
-		mesh: cube.clone(),
-		material: materials.add(Colour::rgb(1.0, 0.0, 0.0).into()),
-		transform: Transform::from_xyz(2.0, 6.0, 0.0).with_scale((0.5, 0.5, 0.5).into()),
-		..default()
-	});
-	commands.spawn(PbrBundle {
-		mesh: cube.clone(),
-		material: materials.add(Colour::rgb(0.0, 1.0, 0.0).into()),
-		transform: Transform::from_xyz(0.0, 8.0, 0.0).with_scale((0.5, 0.5, 0.5).into()),
-		..default()
-	});
-	commands.spawn(PbrBundle {
-		mesh: cube,
-		material: materials.add(Colour::rgb(0.0, 0.0, 1.0).into()),
-		transform: Transform::from_xyz(0.0, 6.0, 2.0).with_scale((0.5, 0.5, 0.5).into()),
-		..default()
-	});
+	commands.spawn((
+		PbrBundle {
+			mesh: cube.clone(),
+			material: materials.add(Colour::rgb(0.0, 0.0, 0.0).into()),
+			transform: Transform::from_xyz(0.0, 6.0, 0.0).with_scale((0.5, 0.5, 0.5).into()),
+			..default()
+		},
+		LevelEntity,
+	));
+	commands.spawn((
+		PbrBundle {
+			mesh: cube.clone(),
+			material: materials.add(Colour::rgb(1.0, 0.0, 0.0).into()),
+			transform: Transform::from_xyz(2.0, 6.0, 0.0).with_scale((0.5, 0.5, 0.5).into()),
+			..default()
+		},
+		LevelEntity,
+	));
+	commands.spawn((
+		PbrBundle {
+			mesh: cube.clone(),
+			material: materials.add(Colour::rgb(0.0, 1.0, 0.0).into()),
+			transform: Transform::from_xyz(0.0, 8.0, 0.0).with_scale((0.5, 0.5, 0.5).into()),
+			..default()
+		},
+		LevelEntity,
+	));
+	commands.spawn((
+		PbrBundle {
+			mesh: cube,
+			material: materials.add(Colour::rgb(0.0, 0.0, 1.0).into()),
+			transform: Transform::from_xyz(0.0, 6.0, 2.0).with_scale((0.5, 0.5, 0.5).into()),
+			..default()
+		},
+		LevelEntity,
+	));
 }
 
 #[derive(Component)]
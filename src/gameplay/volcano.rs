@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use once_cell::sync::OnceCell;
+
+use crate::gameplay::{
+	enemies::{self, DamageType, Damageable, Enemy, EnemyKilled},
+	ui::Click,
+};
+
+type Colour = Color;
+
+// ------------------------------ VOLCANO --------------------------------
+
+const COOLDOWN: f32 = 30.0;
+const IMPACT_DELAY: f32 = 1.5;
+const DROP_HEIGHT: f32 = 15.0;
+const RADIUS: f32 = 3.0;
+const DAMAGE: i32 = 300;
+
+#[derive(Resource, Debug)]
+pub struct Volcano {
+	// Waiting for a world click to pick where the meteor lands
+	pub armed: bool,
+	pub cooldown: Timer,
+}
+
+impl Volcano {
+	pub fn new() -> Self {
+		let mut cooldown = Timer::from_seconds(COOLDOWN, TimerMode::Once);
+		// Available from the start
+		cooldown.tick(cooldown.duration());
+		Volcano {
+			armed: false,
+			cooldown,
+		}
+	}
+
+	pub fn ready(&self) -> bool {
+		self.cooldown.finished()
+	}
+}
+
+#[derive(Component, Debug)]
+pub struct Meteor {
+	target: Vec3,
+	impact: Timer,
+}
+
+static METEOR_MODEL: OnceCell<(Handle<Mesh>, Handle<StandardMaterial>)> = OnceCell::new();
+
+pub fn init_meteor_model(
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	METEOR_MODEL.get_or_init(|| {
+		(
+			meshes.add(
+				shape::UVSphere {
+					radius: 0.6,
+					..default()
+				}
+				.into(),
+			),
+			materials.add(StandardMaterial {
+				base_color: Colour::rgb(1.0, 0.3, 0.0),
+				emissive: Colour::rgb(1.0, 0.3, 0.0),
+				..default()
+			}),
+		)
+	});
+}
+
+pub fn call_meteor(
+	mut clicks: EventReader<Click>,
+	mut volcano: ResMut<Volcano>,
+	mut commands: Commands,
+	time: Res<Time>,
+) {
+	volcano.cooldown.tick(time.delta());
+
+	let (mesh, material) = METEOR_MODEL
+		.get()
+		.expect("Meteor model should've been initialised");
+	for target in clicks.iter().filter_map(|ev| ev.world()) {
+		if !volcano.armed || !volcano.ready() {
+			continue;
+		}
+		volcano.armed = false;
+		volcano.cooldown.reset();
+
+		commands.spawn((
+			PbrBundle {
+				mesh: mesh.clone(),
+				material: material.clone(),
+				transform: Transform::from_translation(target + Vec3::Y * DROP_HEIGHT),
+				..default()
+			},
+			Meteor {
+				target,
+				impact: Timer::from_seconds(IMPACT_DELAY, TimerMode::Once),
+			},
+		));
+	}
+}
+
+pub fn drop_meteors(
+	mut commands: Commands,
+	mut meteors: Query<(Entity, &mut Transform, &mut Meteor), Without<Enemy>>,
+	mut enemies: Query<(&Transform, Damageable), With<Enemy>>,
+	mut kills: EventWriter<EnemyKilled>,
+	time: Res<Time>,
+) {
+	for (entity, mut trans, mut meteor) in meteors.iter_mut() {
+		meteor.impact.tick(time.delta());
+		let height = DROP_HEIGHT * meteor.impact.percent_left();
+		trans.translation = meteor.target + Vec3::Y * height;
+
+		if !meteor.impact.finished() {
+			continue;
+		}
+		commands.entity(entity).despawn_recursive();
+
+		for (enemy_pos, mut target) in enemies.iter_mut() {
+			// Ignore height so flying enemies get hit as well
+			let offset = enemy_pos.translation - meteor.target;
+			if Vec2::new(offset.x, offset.z).length() > RADIUS {
+				continue;
+			}
+			enemies::damage_enemy(&mut target, DAMAGE, DamageType::Fire, &mut kills);
+		}
+	}
+}
@@ -1,58 +1,181 @@
 #![allow(clippy::type_complexity, dead_code)]
 
-use bevy::{pbr::DirectionalLightShadowMap, prelude::*};
+use std::time::Duration;
+
+use bevy::{asset::ChangeWatcher, pbr::DirectionalLightShadowMap, prelude::*};
 use gameplay::{
-	levels::{Difficulty, GameState, SpawnTimer},
-	towers::{Banking, Tower},
-	ui::Click,
+	enemies::{Enemy, EnemyKilled, EnemyLeaked},
+	health_bars::HealthBar,
+	level_data::{LevelData, LevelLoader},
+	levels::{
+		AppState, Difficulty, GameOver, GameState, LevelEnd, LevelEntity, WaveCleared, WaveStarted,
+	},
+	menu::MenuEntity,
+	save::{LoadGame, SaveGame},
+	stats::{Stats, StatsLoader},
+	towers::{Banking, Bullet, PlacementRejected, Tower, UpgradeTower},
+	ui::{Banner, Click},
+	utils::{despawn_with, VisualMarker},
+	volcano::{Meteor, Volcano},
 };
 
-mod easy;
 mod gameplay;
-mod hard;
-mod normal;
 
 fn main() {
 	App::new()
-		.add_plugins(DefaultPlugins)
+		// Watch the assets folder so edited stats apply without a restart
+		.add_plugins(DefaultPlugins.set(AssetPlugin {
+			watch_for_changes: ChangeWatcher::with_delay(Duration::from_millis(200)),
+			..default()
+		}))
 		.add_plugins(bevy::diagnostic::FrameTimeDiagnosticsPlugin)
 		.insert_resource(DirectionalLightShadowMap { size: 8192 })
-		.insert_resource(SpawnTimer(Timer::from_seconds(2.0, TimerMode::Repeating)))
-		.insert_resource(GameState {
-			number: 0,
-			active: true,
-			difficulty: Difficulty::Easy,
-			wave: easy::WAVES[0].clone(),
-		})
-		.insert_resource(Banking {
-			selection: Some(Tower::Land),
-			balance: 1000000,
-		})
+		.insert_resource(GameState::new(Difficulty::Easy, &[]))
+		.insert_resource(Volcano::new())
+		.insert_resource(Banking::new())
+		.add_state::<AppState>()
+		.add_asset::<LevelData>()
+		.init_asset_loader::<LevelLoader>()
+		.add_asset::<Stats>()
+		.init_asset_loader::<StatsLoader>()
 		.add_event::<Click>()
+		.add_event::<EnemyLeaked>()
+		.add_event::<EnemyKilled>()
+		.add_event::<GameOver>()
+		.add_event::<WaveStarted>()
+		.add_event::<WaveCleared>()
+		.add_event::<LevelEnd>()
+		.add_event::<UpgradeTower>()
+		.add_event::<PlacementRejected>()
+		.add_event::<SaveGame>()
+		.add_event::<LoadGame>()
 		.add_systems(
 			Startup,
 			(
-				easy::setup,
-				gameplay::ui::setup_ui,
+				gameplay::level_data::load_levels,
+				gameplay::stats::load_stats,
 				gameplay::ui::init_textures,
 				gameplay::towers::init_bullet_model,
-				gameplay::enemies::init_enemies,
+				gameplay::towers::init_beam_model,
+				gameplay::volcano::init_meteor_model,
+				gameplay::health_bars::init_health_bar_model,
+			),
+		)
+		.add_systems(
+			OnEnter(AppState::MainMenu),
+			(
+				gameplay::menu::setup_menu,
+				despawn_with::<LevelEntity>,
+				despawn_with::<Enemy>,
+				despawn_with::<HealthBar>,
+				despawn_with::<Tower>,
+				despawn_with::<Bullet>,
+				despawn_with::<Meteor>,
+				despawn_with::<VisualMarker>,
+				gameplay::towers::clear_occupied_map,
 			),
 		)
+		.add_systems(
+			OnExit(AppState::MainMenu),
+			(
+				despawn_with::<MenuEntity>,
+				gameplay::levels::setup_level,
+				gameplay::ui::setup_ui,
+				gameplay::ui::setup_boss_bar,
+				gameplay::save::restore_save,
+			),
+		)
+		.add_systems(
+			OnEnter(AppState::Paused),
+			(gameplay::ui::show_pause, gameplay::menu::pause_time),
+		)
+		.add_systems(
+			OnExit(AppState::Paused),
+			(despawn_with::<Banner>, gameplay::menu::resume_time),
+		)
+		.add_systems(OnEnter(AppState::GameOver), gameplay::ui::show_game_over)
+		.add_systems(OnExit(AppState::GameOver), despawn_with::<Banner>)
+		.add_systems(OnEnter(AppState::Victory), gameplay::ui::show_level_end)
+		.add_systems(OnExit(AppState::Victory), despawn_with::<Banner>)
 		.add_systems(
 			Update,
 			(
+				gameplay::stats::sync_stats,
+				gameplay::menu::run_menu,
+				gameplay::menu::start_level,
+				gameplay::menu::list_levels,
+				gameplay::menu::toggle_pause,
+				gameplay::save::save_game,
+				gameplay::save::load_game,
+			),
+		)
+		.add_systems(
+			Update,
+			(
+				gameplay::enemies::update_speed.before(gameplay::enemies::move_enemies),
 				gameplay::enemies::move_enemies,
 				gameplay::enemies::animate_enemies,
-				gameplay::cursor::move_cursor_and_camera,
+				gameplay::enemies::burn_enemies,
+				gameplay::enemies::thaw_enemies,
+				gameplay::enemies::tint_slowed,
+				gameplay::enemies::kill_enemies,
+				gameplay::enemies::boss_phases,
+				gameplay::enemies::expire_immunity,
+				gameplay::levels::spawn_enemy,
+				gameplay::levels::clear_wave,
+				gameplay::levels::lose_lives,
+				gameplay::levels::end_game,
+			)
+				.run_if(in_state(AppState::Playing)),
+		)
+		.add_systems(
+			Update,
+			(
+				gameplay::health_bars::attach_health_bars,
+				gameplay::health_bars::update_health_bars,
+				gameplay::health_bars::remove_health_bars,
+				gameplay::health_bars::orient_health_bars
+					.after(gameplay::enemies::move_enemies)
+					.after(gameplay::enemies::animate_enemies),
+			)
+				.run_if(in_state(AppState::Playing)),
+		)
+		.add_systems(
+			Update,
+			(
 				gameplay::towers::move_bullets,
 				gameplay::towers::spawn_tower,
+				gameplay::towers::sell_tower,
+				// Despawns only land at the end of the frame, a select after the sale
+				// would still find the sold tower
+				gameplay::towers::select_tower.before(gameplay::towers::sell_tower),
+				gameplay::towers::upgrade_tower,
 				gameplay::towers::land_attack,
 				gameplay::towers::air_attack,
-				gameplay::levels::spawn_enemy,
+				gameplay::towers::beam_attack,
+				gameplay::volcano::call_meteor.after(gameplay::towers::select_tower),
+				gameplay::volcano::drop_meteors,
+			)
+				.run_if(in_state(AppState::Playing)),
+		)
+		.add_systems(
+			Update,
+			(
+				gameplay::cursor::move_cursor_and_camera,
+				gameplay::cursor::show_range,
 				gameplay::ui::run_shop,
+				gameplay::ui::run_game_controls,
 				gameplay::ui::generate_clicks,
-			),
+				gameplay::ui::update_balance_label,
+				gameplay::ui::update_lives_label,
+				gameplay::ui::update_status_label,
+				gameplay::ui::show_volcano_cooldown,
+				gameplay::ui::show_boss_health,
+				gameplay::ui::show_inspect_panel,
+				gameplay::ui::show_game_speed,
+				gameplay::menu::change_speed,
+			)
+				.run_if(in_state(AppState::Playing)),
 		)
 		.run();
 }